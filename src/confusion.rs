@@ -0,0 +1,66 @@
+use crate::optimal_alignments::{Alignment, Element};
+use std::collections::HashMap;
+
+/// counts of presented characters transcribed as each character
+///
+/// Omissions are counted in the column of `Element::Null`
+/// and insertions in the row of `Element::Null`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfusionMatrix {
+    counts: HashMap<(Element, Element), u128>,
+}
+
+impl ConfusionMatrix {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// count aligned positions of an alignment
+    ///
+    /// Positions aligning NULL with NULL are skipped.
+    pub fn record_alignment(&mut self, alignment: &Alignment) {
+        alignment.presented.iter()
+            .zip(alignment.transcribed.iter())
+            .filter(|(p, t)| !p.is_null() || !t.is_null())
+            .for_each(|(p, t)| {
+                *self.counts.entry((p.clone(), t.clone())).or_insert(0) += 1;
+            });
+    }
+
+    /// number of times `presented` was transcribed as `transcribed`
+    pub fn get(&self, presented: Element, transcribed: Element) -> u128 {
+        self.counts.get(&(presented, transcribed))
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    /// iterate pairs of presented and transcribed elements with their counts in arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item=(&(Element, Element), &u128)> {
+        self.counts.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record_alignment_test() {
+        let c = Element::Character;
+        let alignment = Alignment {
+            presented: vec![c('a'), c('b'), Element::Null, c('c'), Element::Null],
+            transcribed: vec![c('a'), c('d'), c('e'), Element::Null, Element::Null],
+        };
+
+        let mut matrix = ConfusionMatrix::new();
+        matrix.record_alignment(&alignment);
+        matrix.record_alignment(&alignment);
+
+        assert_eq!(matrix.get(c('a'), c('a')), 2);
+        assert_eq!(matrix.get(c('b'), c('d')), 2);
+        assert_eq!(matrix.get(Element::Null, c('e')), 2);
+        assert_eq!(matrix.get(c('c'), Element::Null), 2);
+        assert_eq!(matrix.get(Element::Null, Element::Null), 0);
+        assert_eq!(matrix.iter().count(), 4);
+    }
+}
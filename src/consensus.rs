@@ -0,0 +1,120 @@
+use crate::cost::Cost;
+use crate::optimal_alignments::{msd, weighted_alignment, Element};
+use std::collections::HashMap;
+
+/// unit costs of edit operations
+struct UnitCost;
+
+impl Cost for UnitCost {
+    fn substitution(&self, _expected: char, _got: char) -> f64 {
+        1.0
+    }
+
+    fn gap(&self, _c: char) -> f64 {
+        1.0
+    }
+}
+
+/// consensus of transcriptions by majority vote, as a reference text for crowdsourced transcriptions
+///
+/// A multiple-sequence alignment is approximated by pairwise alignments anchored to a transcription:
+/// the anchor is the transcription of the least total minimum string distance to the others
+/// (the first one among ties), and every transcription is aligned to it.
+/// Each character of the anchor is replaced by the most common character aligned to it,
+/// or dropped if omissions are the most common (the anchor's own character wins ties).
+/// Characters inserted between characters of the anchor are kept
+/// if more than half of the transcriptions insert the same string there.
+/// Unlike a multiple-sequence alignment, insertions of different transcriptions are not aligned with each other.
+///
+/// An empty string for no transcriptions.
+pub fn consensus_reference(transcriptions: &[&str]) -> String {
+    let transcriptions = transcriptions.iter()
+        .map(|t| t.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let total_distance = |anchor: &[char]| -> u128 {
+        transcriptions.iter()
+            .map(|t| msd(anchor, t)[anchor.len()][t.len()])
+            .sum()
+    };
+    let anchor = match transcriptions.iter().min_by_key(|t| total_distance(t)) {
+        Some(anchor) => anchor,
+        None => return String::new(),
+    };
+
+    // votes for each character of the anchor, and inserted strings before each of them (and at the end)
+    let mut votes = vec![HashMap::<Option<char>, usize>::new(); anchor.len()];
+    let mut insertions = vec![HashMap::<String, usize>::new(); anchor.len() + 1];
+
+    for transcription in transcriptions.iter() {
+        let alignment = weighted_alignment(anchor, transcription, &UnitCost)
+            .expect("unit costs are finite and positive");
+        let (mut position, mut inserted) = (0, String::new());
+
+        for (a, t) in alignment.presented.iter().zip(alignment.transcribed.iter()) {
+            match (a, t) {
+                (Element::Null, Element::Character(c)) => inserted.push(*c),
+                (Element::Character(_), t) => {
+                    if !inserted.is_empty() {
+                        *insertions[position].entry(std::mem::take(&mut inserted)).or_insert(0) += 1;
+                    }
+                    let vote = match t {
+                        Element::Character(c) => Some(*c),
+                        Element::Null => None,
+                    };
+                    *votes[position].entry(vote).or_insert(0) += 1;
+                    position += 1;
+                }
+                (Element::Null, Element::Null) => {}
+            }
+        }
+        if !inserted.is_empty() {
+            *insertions[position].entry(inserted).or_insert(0) += 1;
+        }
+    }
+
+    let mut consensus = String::new();
+    for position in 0..=anchor.len() {
+        let majority = insertions[position].iter()
+            .filter(|(_, &n)| n * 2 > transcriptions.len())
+            .map(|(inserted, _)| inserted);
+        if let Some(inserted) = majority.min() {
+            consensus.push_str(inserted);
+        }
+
+        if position < anchor.len() {
+            let own = Some(anchor[position]);
+            let winner = votes[position].iter()
+                .max_by(|(a, na), (b, nb)| {
+                    na.cmp(nb)
+                        .then_with(|| (**a == own).cmp(&(**b == own)))
+                        .then_with(|| b.cmp(a))
+                })
+                .and_then(|(c, _)| *c);
+            if let Some(c) = winner {
+                consensus.push(c);
+            }
+        }
+    }
+
+    consensus
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn consensus_reference_test() {
+        let transcriptions = ["the quick brown fox", "the quikc brown fox", "teh quick brown fix"];
+        assert_eq!(consensus_reference(&transcriptions), "the quick brown fox");
+
+        // an omission and an insertion of a single transcription are outvoted
+        assert_eq!(consensus_reference(&["the fx", "the fox", "the fox"]), "the fox");
+        assert_eq!(consensus_reference(&["the foox", "the fox", "the fox"]), "the fox");
+        assert_eq!(consensus_reference(&["the fox!", "the fox!", "the fox"]), "the fox!");
+
+        assert_eq!(consensus_reference(&["the fox"]), "the fox");
+        assert_eq!(consensus_reference(&[]), "");
+    }
+}
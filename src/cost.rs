@@ -0,0 +1,119 @@
+use crate::distribution::Distribution;
+
+/// costs of edit operations of a weighted minimum string distance
+///
+/// See [`OptimalAlignments::with_cost`](crate::OptimalAlignments::with_cost).
+pub trait Cost {
+    /// cost of transcribing `expected` as `got`, which differ
+    fn substitution(&self, expected: char, got: char) -> f64;
+
+    /// cost of inserting or omitting the character
+    fn gap(&self, c: char) -> f64;
+}
+
+/// costs of substitutions weighted by how surprising they are under the distribution
+///
+/// A substitution costs `substitution * (1 + |log2 p(expected) - log2 p(got)|)`,
+/// so swapping a rare character for a common one costs more than swapping two common ones.
+/// Characters not in the distribution (or of probability 0) cost `substitution`.
+/// A gap costs `gap`.
+#[derive(Debug, Clone)]
+pub struct ProbabilityAwareCost<'a> {
+    distribution: &'a Distribution,
+    substitution: f64,
+    gap: f64,
+}
+
+impl<'a> ProbabilityAwareCost<'a> {
+    pub fn new(distribution: &'a Distribution) -> Self {
+        Self {
+            distribution,
+            substitution: 1.0,
+            gap: 1.0,
+        }
+    }
+
+    /// set a base cost of a substitution (default: 1)
+    pub fn substitution(mut self, substitution: f64) -> Self {
+        self.substitution = substitution;
+        self
+    }
+
+    /// set a cost of an insertion or an omission (default: 1)
+    pub fn gap(mut self, gap: f64) -> Self {
+        self.gap = gap;
+        self
+    }
+}
+
+impl<'a> Cost for ProbabilityAwareCost<'a> {
+    fn substitution(&self, expected: char, got: char) -> f64 {
+        let log2 = |c: char| {
+            self.distribution.get(c)
+                .filter(|&p| p > 0.0)
+                .map(f64::log2)
+        };
+
+        match (log2(expected), log2(got)) {
+            (Some(expected), Some(got)) => self.substitution * (1.0 + (expected - got).abs()),
+            _ => self.substitution,
+        }
+    }
+
+    fn gap(&self, _: char) -> f64 {
+        self.gap
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{OptimalAlignments, TetError};
+
+    #[test]
+    fn probability_aware_cost_test() {
+        let distribution = Distribution::default();
+        let cost = ProbabilityAwareCost::new(&distribution);
+
+        // 'z' is rare, and 'e' and 't' are common
+        let rare = Cost::substitution(&cost, 'z', 'e');
+        let common = Cost::substitution(&cost, 't', 'e');
+        assert!(rare > common);
+        assert!(common >= 1.0);
+        assert_eq!(Cost::substitution(&cost, 'z', 'e'), Cost::substitution(&cost, 'e', 'z'));
+        assert_eq!(Cost::substitution(&cost, '1', 'e'), 1.0);
+
+        // substituting 'e' for 'z' costs more than omitting 'z' and inserting 'e'
+        let weighted = OptimalAlignments::with_cost("ze", "ee", &distribution, &cost, &Default::default()).unwrap();
+        assert_eq!(weighted.n_substitutions(), 0);
+        assert_eq!(weighted.n_omissions(), 1);
+        assert_eq!(weighted.n_insertions(), 1);
+
+        let unit = OptimalAlignments::new("ze", "ee", &distribution).unwrap();
+        assert_eq!(unit.n_substitutions(), 1);
+
+        // but substituting 'e' for 't' is cheaper than gaps
+        let weighted = OptimalAlignments::with_cost("te", "ee", &distribution, &cost, &Default::default()).unwrap();
+        assert_eq!(weighted.n_substitutions(), 1);
+        assert!(weighted.ixy().unwrap().is_finite());
+    }
+
+    #[test]
+    fn large_cost_test() {
+        let distribution = Distribution::default();
+
+        // distances beyond an absolute tolerance of rounding errors
+        let cost = ProbabilityAwareCost::new(&distribution).gap(12345678.9);
+        let weighted = OptimalAlignments::with_cost("the quick brown fox jumps", "teh", &distribution, &cost, &Default::default()).unwrap();
+        assert_eq!(weighted.n_omissions(), 22);
+        assert_eq!(weighted.n_insertions(), 0);
+
+        for &invalid in [-1.0, f64::NAN, f64::INFINITY].iter() {
+            let cost = ProbabilityAwareCost::new(&distribution).gap(invalid);
+            assert!(matches!(
+                OptimalAlignments::with_cost("the", "teh", &distribution, &cost, &Default::default()),
+                Err(TetError::InvalidCost(_)),
+            ));
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::distribution::Frequencies;
+use crate::error::{Result, TetError};
+
+/// incremental counter of characters from chunks of UTF-8 bytes
+///
+/// A code point split across chunks is buffered until its remaining bytes arrive.
+#[derive(Debug, Default)]
+pub struct FrequencyCounter {
+    frequencies: Frequencies,
+    /// bytes of an incomplete code point at the end of the last chunk
+    pending: Vec<u8>,
+    /// whether an invalid UTF-8 sequence was found
+    invalid: bool,
+}
+
+impl FrequencyCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// record characters of a chunk of bytes
+    ///
+    /// Bytes after an invalid UTF-8 sequence are ignored.
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        if self.invalid {
+            return;
+        }
+
+        let mut buffer = std::mem::take(&mut self.pending);
+        buffer.extend_from_slice(bytes);
+
+        let (valid, rest) = match std::str::from_utf8(&buffer) {
+            Ok(s) => (s, &[][..]),
+            Err(e) => {
+                if e.error_len().is_some() {
+                    self.invalid = true;
+                }
+
+                let (valid, rest) = buffer.split_at(e.valid_up_to());
+                (std::str::from_utf8(valid).unwrap(), rest)
+            }
+        };
+
+        valid.chars()
+            .for_each(|c| self.frequencies.record(c));
+
+        if !self.invalid {
+            self.pending = rest.to_vec();
+        }
+    }
+
+    /// get the frequencies of recorded characters
+    ///
+    /// Fails if the bytes contain an invalid or truncated UTF-8 sequence.
+    pub fn finish(self) -> Result<Frequencies> {
+        if self.invalid || !self.pending.is_empty() {
+            Err(TetError::InvalidUtf8)
+        } else {
+            Ok(self.frequencies)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn split_code_point_test() {
+        let bytes = "aうa".as_bytes();
+
+        let mut counter = FrequencyCounter::new();
+        // 'う' is 3 bytes: split after its first byte
+        counter.push_bytes(&bytes[..2]);
+        counter.push_bytes(&bytes[2..3]);
+        counter.push_bytes(&bytes[3..]);
+        let frequencies = counter.finish().unwrap();
+
+        let mut answer = Frequencies::new();
+        "aうa".chars().for_each(|c| answer.record(c));
+
+        assert_eq!(frequencies, answer);
+    }
+
+    #[test]
+    fn invalid_utf8_test() {
+        let mut counter = FrequencyCounter::new();
+        counter.push_bytes(b"ab\xffcd");
+        assert_eq!(counter.finish(), Err(TetError::InvalidUtf8));
+
+        let mut counter = FrequencyCounter::new();
+        counter.push_bytes(&"う".as_bytes()[..2]);
+        assert_eq!(counter.finish(), Err(TetError::InvalidUtf8));
+    }
+}
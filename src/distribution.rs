@@ -1,6 +1,35 @@
+use crate::error::{CoverageError, TetError};
+use crate::statistics::approx_eq;
 #[cfg(feature = "serde1")]
 use serde::{Serialize, Deserialize};
+use std::collections::hash_map::Keys;
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::io;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
+
+mod bigram;
+mod builder;
+
+pub use bigram::{bigram_entropy, BigramDistribution};
+pub use builder::DistributionBuilder;
+
+/// source of symbols with their probabilities
+pub trait Source {
+    type Symbol: Clone + Eq + Hash + Debug;
+
+    /// p(i)
+    fn p(&self, symbol: &Self::Symbol) -> Option<&f64>;
+
+    /// symbols of the source
+    fn symbols(&self) -> Keys<'_, Self::Symbol, f64>;
+
+    /// H(X): entropy
+    fn hx(&self) -> f64;
+}
 
 /// frequency of characters
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
@@ -30,23 +59,121 @@ impl Frequencies {
         }
     }
 
+    /// record appearances of all characters of the text
+    pub fn record_str(&mut self, s: &str) {
+        s.chars()
+            .for_each(|c| self.record(c));
+    }
+
+    /// count characters of lines, e.g. of [`BufRead::lines`](std::io::BufRead::lines)
+    ///
+    /// Line breaks are not counted as the lines do not contain them.
+    /// Stops at the first error and returns it.
+    pub fn from_line_results<I: IntoIterator<Item=io::Result<String>>>(lines: I) -> io::Result<Frequencies> {
+        let mut frequencies = Frequencies::new();
+        for line in lines {
+            frequencies.record_str(&line?);
+        }
+
+        Ok(frequencies)
+    }
+
+    /// add counts of other frequencies
+    pub fn merge(&mut self, other: &Frequencies) {
+        for (&c, &n) in other.map.iter() {
+            *self.map.entry(c).or_insert(0) += n;
+        }
+    }
+
+    /// count characters of chunks in parallel
+    #[cfg(feature = "rayon")]
+    pub fn from_par_chunks(chunks: &[&str]) -> Self {
+        use rayon::prelude::*;
+
+        chunks.par_iter()
+            .map(|chunk| {
+                let mut frequencies = Frequencies::new();
+                frequencies.record_str(chunk);
+                frequencies
+            })
+            .reduce(Frequencies::new, |mut acc, frequencies| {
+                acc.merge(&frequencies);
+                acc
+            })
+    }
+
     pub fn n(&self) -> u128 {
         self.map.values().sum::<u128>()
     }
 
+    /// number of appearances of the character
+    pub fn count(&self, c: char) -> u128 {
+        self.map.get(&c).copied().unwrap_or(0)
+    }
+
+    /// iterate characters and their counts in arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item=(&char, &u128)> {
+        self.map.iter()
+    }
+
+    /// `k` most common characters in descending order of counts
+    ///
+    /// Ties are broken by the smaller character.
+    pub fn most_common(&self, k: usize) -> Vec<(char, u128)> {
+        let mut counts = self.map.iter()
+            .map(|(&c, &n)| (c, n))
+            .collect::<Vec<_>>();
+        counts.sort_by(|(a, na), (b, nb)| nb.cmp(na).then_with(|| a.cmp(b)));
+        counts.truncate(k);
+
+        counts
+    }
+
     pub fn retain<F: Fn(&char) -> bool>(&mut self, func: F) {
         self.map.retain(|c, _| func(c))
     }
 
+    /// retain alphabetic characters and space
+    ///
+    /// If `lowercase`, counts of characters are merged into their lowercase.
+    pub fn retain_alphabetic(&mut self, lowercase: bool) {
+        self.retain_class(|c| c.is_alphabetic() || *c == ' ', lowercase)
+    }
+
+    /// retain alphanumeric characters and space
+    ///
+    /// If `lowercase`, counts of characters are merged into their lowercase.
+    pub fn retain_alphanumeric(&mut self, lowercase: bool) {
+        self.retain_class(|c| c.is_alphanumeric() || *c == ' ', lowercase)
+    }
+
+    /// retain printable ASCII characters (including space)
+    ///
+    /// If `lowercase`, counts of characters are merged into their lowercase.
+    pub fn retain_ascii_printable(&mut self, lowercase: bool) {
+        self.retain_class(|c| c.is_ascii_graphic() || *c == ' ', lowercase)
+    }
+
+    fn retain_class<F: Fn(&char) -> bool>(&mut self, func: F, lowercase: bool) {
+        self.retain(func);
+
+        if lowercase {
+            let map = std::mem::take(&mut self.map);
+            for (c, n) in map {
+                for lower in c.to_lowercase() {
+                    *self.map.entry(lower).or_insert(0) += n;
+                }
+            }
+        }
+    }
+
     pub fn entry_char(&mut self, c: char) {
-        if !self.map.contains_key(&c) {
-            self.map.insert(c, 0);
-        } else {}
+        self.map.entry(c).or_insert(0);
     }
 
     pub fn replace_char<F: Fn(&char) -> Option<char>>(&mut self, f: F) {
         self.map.iter()
-            .flat_map(|(k, v)| Some((f(k)?, v.clone())))
+            .flat_map(|(k, v)| Some((f(k)?, *v)))
             .collect::<Vec<(char, u128)>>()
             .into_iter()
             .for_each(|(k, v)| {
@@ -56,9 +183,69 @@ impl Frequencies {
     }
 }
 
+impl Default for Frequencies {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AddAssign<&Frequencies> for Frequencies {
+    fn add_assign(&mut self, other: &Frequencies) {
+        self.merge(other);
+    }
+}
+
+impl AddAssign for Frequencies {
+    fn add_assign(&mut self, other: Frequencies) {
+        self.merge(&other);
+    }
+}
+
+impl Add for Frequencies {
+    type Output = Frequencies;
+
+    fn add(mut self, other: Frequencies) -> Frequencies {
+        self += other;
+        self
+    }
+}
+
+impl Sum for Frequencies {
+    fn sum<I: Iterator<Item=Frequencies>>(iter: I) -> Self {
+        iter.fold(Frequencies::new(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Frequencies> for Frequencies {
+    fn sum<I: Iterator<Item=&'a Frequencies>>(iter: I) -> Self {
+        iter.fold(Frequencies::new(), |mut acc, frequencies| {
+            acc += frequencies;
+            acc
+        })
+    }
+}
+
+/// summary statistics of a distribution
+#[cfg_attr(feature = "serde1", derive(Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistributionStats {
+    /// number of characters
+    pub size: usize,
+    /// H(X): entropy
+    pub entropy: f64,
+    /// H_max: log2(number of characters)
+    pub entropy_max: f64,
+    /// 2^H(X): effective number of equally likely characters
+    pub perplexity: f64,
+    /// 1 - H(X) / H_max (0 for a distribution of a single or no character)
+    pub redundancy: f64,
+    /// the most probable character and its probability
+    pub most_common: Option<(char, f64)>,
+}
+
 /// distribution of characters
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Distribution {
     /// map of distribution
     pub(crate) map: HashMap<char, f64>,
@@ -82,17 +269,931 @@ impl Distribution {
         Self { map }
     }
 
+    /// distribution of counts of characters
+    ///
+    /// Counts of a duplicated character are summed.
+    /// Fails if no character is counted.
+    pub fn from_counts(counts: &[(char, u128)]) -> Result<Self, TetError> {
+        let mut map = HashMap::new();
+        for &(c, n) in counts {
+            *map.entry(c).or_insert(0) += n;
+        }
+
+        if map.values().all(|&n| n == 0) {
+            return Err(TetError::EmptyDistribution);
+        }
+
+        Ok(Self::new(Frequencies::with_map(map)))
+    }
+
+    /// distribution of characters of the text
+    ///
+    /// ```
+    /// use tet_rs::Distribution;
+    ///
+    /// let distribution = Distribution::from_text("large and appropriate text");
+    ///
+    /// // 26 characters, 4 of which are 'a'
+    /// assert_eq!(distribution.get('a'), Some(4.0 / 26.0));
+    /// assert_eq!(distribution.get(' '), Some(3.0 / 26.0));
+    /// assert_eq!(distribution.get('z'), None);
+    /// ```
+    pub fn from_text(s: &str) -> Self {
+        Self::from_texts(std::iter::once(s))
+    }
+
+    /// distribution of characters of all the texts
+    pub fn from_texts<'a, I: IntoIterator<Item=&'a str>>(texts: I) -> Self {
+        let mut frequencies = Frequencies::new();
+        texts.into_iter()
+            .for_each(|s| frequencies.record_str(s));
+
+        Self::new(frequencies)
+    }
+
+    /// retain characters which satisfy the predicate,
+    /// and renormalize their probabilities to sum to 1
+    pub fn retain<F: Fn(&char) -> bool>(&mut self, func: F) {
+        self.map.retain(|c, _| func(c));
+        self.renormalize();
+    }
+
+    /// fold characters of the text into the distribution
+    ///
+    /// The current distribution is treated as a prior of `prior_weight` pseudo-counts:
+    /// p(c) becomes `(prior_weight * p(c) + N(c)) / (prior_weight + N)`,
+    /// where N(c) is the count of c in the text and N the number of characters.
+    /// A large weight keeps the distribution stable, a small one follows the text.
+    pub fn update_from_text(&mut self, s: &str, prior_weight: f64) -> Result<(), TetError> {
+        if prior_weight.is_nan() || prior_weight < 0.0 {
+            return Err(TetError::NegativeWeight(prior_weight));
+        }
+
+        let mut frequencies = Frequencies::new();
+        frequencies.record_str(s);
+
+        let total = prior_weight + frequencies.n() as f64;
+        if total <= 0.0 {
+            return Ok(());
+        }
+
+        self.map.values_mut()
+            .for_each(|p| *p *= prior_weight);
+        for (&c, &n) in frequencies.map.iter() {
+            *self.map.entry(c).or_insert(0.0) += n as f64;
+        }
+        self.map.values_mut()
+            .for_each(|p| *p /= total);
+
+        Ok(())
+    }
+
+    /// add a character with probability `p`,
+    /// and scale probabilities of the other characters to sum to `1 - p`
+    ///
+    /// The probability of the character is replaced if it already exists.
+    pub fn add_char(&mut self, c: char, p: f64) -> Result<(), TetError> {
+        if p.is_nan() || p < 0.0 {
+            return Err(TetError::NegativeProbability(c));
+        }
+        if p >= 1.0 {
+            return Err(TetError::ProbabilityTooLarge(c));
+        }
+
+        let rest = self.map.iter()
+            .filter(|(&k, _)| k != c)
+            .map(|(_, &v)| v)
+            .sum::<f64>();
+        if rest <= 0.0 {
+            return Err(TetError::EmptyDistribution);
+        }
+
+        self.map.remove(&c);
+        self.map.values_mut()
+            .for_each(|v| *v *= (1.0 - p) / rest);
+        self.map.insert(c, p);
+
+        Ok(())
+    }
+
+    fn renormalize(&mut self) {
+        let sum = self.map.values().sum::<f64>();
+
+        if sum > 0.0 {
+            self.map.values_mut()
+                .for_each(|p| *p /= sum);
+        }
+    }
+
+    /// combine distributions with weights (e.g. token counts of corpora)
+    ///
+    /// Probabilities are summed with weights over all characters and renormalized.
+    pub fn combine(parts: &[(Distribution, f64)]) -> Result<Self, TetError> {
+        if let Some((_, w)) = parts.iter().find(|(_, w)| w.is_nan() || *w < 0.0) {
+            return Err(TetError::NegativeWeight(*w));
+        }
+
+        let mut map = HashMap::new();
+        for (distribution, w) in parts {
+            for (&c, &p) in distribution.map.iter() {
+                *map.entry(c).or_insert(0.0) += w * p;
+            }
+        }
+
+        Self::normalized(map)
+    }
+
+    /// validate a map of probabilities and normalize it to sum to 1
+    pub(crate) fn normalized(map: HashMap<char, f64>) -> Result<Self, TetError> {
+        if let Some((&c, _)) = map.iter().find(|(_, p)| p.is_nan() || **p < 0.0) {
+            return Err(TetError::NegativeProbability(c));
+        }
+
+        let sum = map.values().sum::<f64>();
+        if sum.is_nan() || sum <= 0.0 {
+            return Err(TetError::EmptyDistribution);
+        }
+
+        let map = map.into_iter()
+            .map(|(c, p)| (c, p / sum))
+            .collect();
+
+        Ok(Self { map })
+    }
+
     pub(crate) fn p(&self, c: &char) -> Option<&f64> {
         self.map.get(c)
     }
 
+    /// probability of the character
+    pub fn get(&self, c: char) -> Option<f64> {
+        self.p(&c).cloned()
+    }
+
+    /// probability of the character, 0 if absent
+    pub fn probability_of(&self, c: char) -> f64 {
+        self.get(c).unwrap_or(0.0)
+    }
+
+    /// the most probable character and its probability
+    ///
+    /// Ties are broken by the smaller character.
+    pub fn most_common_char(&self) -> Option<(char, f64)> {
+        self.map.iter()
+            .max_by(|(a, pa), (b, pb)| pa.total_cmp(pb).then_with(|| b.cmp(a)))
+            .map(|(&c, &p)| (c, p))
+    }
+
+    /// whether the distribution has the character
+    pub fn contains(&self, c: char) -> bool {
+        self.map.contains_key(&c)
+    }
+
+    /// distinct characters of the text absent from the distribution, in order of first appearance
+    pub fn missing_chars(&self, text: &str) -> Vec<char> {
+        let mut missing = Vec::new();
+        for c in text.chars() {
+            if !self.contains(c) && !missing.contains(&c) {
+                missing.push(c);
+            }
+        }
+
+        missing
+    }
+
+    /// whether the distribution has exactly the required characters, no more, no less
+    pub fn covers(&self, required: &[char]) -> Result<(), CoverageError> {
+        let mut missing = Vec::new();
+        for &c in required {
+            if !self.contains(c) && !missing.contains(&c) {
+                missing.push(c);
+            }
+        }
+        let mut extra = self.map.keys()
+            .filter(|c| !required.contains(c))
+            .cloned()
+            .collect::<Vec<_>>();
+        extra.sort_unstable();
+
+        if missing.is_empty() && extra.is_empty() {
+            Ok(())
+        } else {
+            Err(CoverageError { missing, extra })
+        }
+    }
+
+    /// map of characters to their probabilities
+    pub fn as_map(&self) -> &HashMap<char, f64> {
+        &self.map
+    }
+
+    /// iterate characters and their probabilities in arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item=(&char, &f64)> {
+        self.map.iter()
+    }
+
+    /// p(c) of this minus p(c) of the other for each character of either, treating missing characters as 0
+    ///
+    /// Sorted in descending order of absolute differences; ties are broken by the smaller character.
+    pub fn difference(&self, other: &Distribution) -> Vec<(char, f64)> {
+        let mut difference = self.map.keys()
+            .chain(other.map.keys().filter(|c| !self.map.contains_key(c)))
+            .map(|&c| (c, self.probability_of(c) - other.probability_of(c)))
+            .collect::<Vec<_>>();
+        difference.sort_by(|(a, da), (b, db)| db.abs().total_cmp(&da.abs()).then_with(|| a.cmp(b)));
+
+        difference
+    }
+
+    /// whether probabilities agree within `tol`, treating missing characters as 0
+    ///
+    /// Unlike `==`, explicitly stored zero entries are ignored.
+    pub fn eq_ignoring_zeros(&self, other: &Distribution, tol: f64) -> bool {
+        let agree = |a: &Distribution, b: &Distribution| {
+            a.iter()
+                .filter(|(_, &p)| p > 0.0)
+                .all(|(c, &p)| approx_eq(p, b.get(*c).unwrap_or(0.0), tol))
+        };
+
+        agree(self, other) && agree(other, self)
+    }
+
+    /// H_max: maximum entropy over the characters, log2(number of characters)
+    ///
+    /// It is 0 for a distribution of a single (or no) character.
+    pub fn entropy_max(&self) -> f64 {
+        if self.map.len() <= 1 {
+            0.0
+        } else {
+            (self.map.len() as f64).log2()
+        }
+    }
+
     /// H(X): entropy
     pub fn hx(&self) -> f64 {
         -self.map.iter()
+            // 0 log 0 = 0
+            .filter(|(_, &pi)| pi > 0.0)
             .map(|(_, &pi)| {
                 pi * pi.log2()
             })
             .sum::<f64>()
     }
+
+    /// -p_i log2 p_i: contribution of each character to H(X), in descending order
+    ///
+    /// Contributions sum up to H(X); a character of probability 0 contributes 0.
+    /// Ties are broken by the smaller character.
+    pub fn entropy_contributions(&self) -> Vec<(char, f64)> {
+        let mut contributions = self.map.iter()
+            .map(|(&c, &pi)| {
+                // 0 log 0 = 0
+                let contribution = if pi > 0.0 { -pi * pi.log2() } else { 0.0 };
+                (c, contribution)
+            })
+            .collect::<Vec<_>>();
+        contributions.sort_by(|(a, ha), (b, hb)| hb.total_cmp(ha).then_with(|| a.cmp(b)));
+
+        contributions
+    }
+
+    /// H_α(X): Rényi entropy of order `alpha` (bits)
+    ///
+    /// log2(\sum p_i^α) / (1 - α) for `alpha >= 0`, which is
+    /// log2 of the number of characters of positive probabilities for `alpha = 0`,
+    /// H(X) for `alpha = 1`, the collision entropy for `alpha = 2`
+    /// and the min-entropy -log2(max p_i) for `alpha = ∞`.
+    /// `NaN` for a negative or `NaN` `alpha`.
+    pub fn renyi_entropy(&self, alpha: f64) -> f64 {
+        if alpha.is_nan() || alpha < 0.0 {
+            return f64::NAN;
+        }
+        if alpha == 1.0 {
+            return self.hx();
+        }
+
+        let positive = self.map.values()
+            .cloned()
+            .filter(|&pi| pi > 0.0);
+
+        if alpha == 0.0 {
+            return (positive.count() as f64).log2();
+        }
+        if alpha == f64::INFINITY {
+            return -positive.fold(0.0, f64::max).log2();
+        }
+
+        if (alpha - 1.0).abs() > 0.5 {
+            return positive.map(|pi| pi.powf(alpha)).sum::<f64>().log2() / (1.0 - alpha);
+        }
+
+        // \sum p_i^α - 1 = \sum p_i (p_i^(α-1) - 1) + (\sum p_i - 1),
+        // without cancellation near α = 1
+        let deviation = positive.clone()
+            .map(|pi| pi * ((alpha - 1.0) * pi.ln()).exp_m1())
+            .sum::<f64>();
+        let sum_minus_one = deviation + (positive.sum::<f64>() - 1.0);
+
+        sum_minus_one.ln_1p() / std::f64::consts::LN_2 / (1.0 - alpha)
+    }
+
+    /// distribution of p(c)^(1/t), renormalized
+    ///
+    /// `t > 1` flattens the distribution toward uniform, and `t < 1` sharpens it toward the mode.
+    pub fn with_temperature(&self, t: f64) -> Result<Distribution, TetError> {
+        if t.is_nan() || t <= 0.0 {
+            return Err(TetError::InvalidTemperature(t));
+        }
+
+        // scaled by the largest probability to avoid underflow
+        let max = self.map.values()
+            .fold(f64::NEG_INFINITY, |max, p| max.max(p.ln()));
+
+        let map = self.map.iter()
+            .map(|(&c, &p)| (c, ((p.ln() - max) / t).exp()))
+            .collect();
+
+        let mut distribution = Self { map };
+        distribution.renormalize();
+
+        Ok(distribution)
+    }
+
+    /// cross-entropy of the text against the distribution (bits/character)
+    ///
+    /// -(1/N) \sum log2 p(c) over N characters of the text.
+    /// All characters absent from the distribution are listed in the error.
+    pub fn text_cross_entropy(&self, text: &str) -> Result<f64, TetError> {
+        let mut unknown = text.chars()
+            .filter(|c| !self.map.contains_key(c))
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            unknown.sort_unstable();
+            unknown.dedup();
+            return Err(TetError::UnknownCharacters(unknown));
+        }
+
+        let (n, sum) = text.chars()
+            .fold((0usize, 0.0), |(n, sum), c| (n + 1, sum + self.map[&c].log2()));
+        if n == 0 {
+            return Err(TetError::EmptyInput);
+        }
+
+        Ok(-sum / n as f64)
+    }
+
+    /// summarize the distribution
+    ///
+    /// Ties of the most common character are broken by the smaller character.
+    pub fn statistics(&self) -> DistributionStats {
+        let entropy = self.hx();
+        let entropy_max = self.entropy_max();
+
+        let redundancy = if entropy_max > 0.0 {
+            1.0 - entropy / entropy_max
+        } else {
+            0.0
+        };
+
+        DistributionStats {
+            size: self.map.len(),
+            entropy,
+            entropy_max,
+            perplexity: entropy.exp2(),
+            redundancy,
+            most_common: self.most_common_char(),
+        }
+    }
+}
+
+impl Source for Distribution {
+    type Symbol = char;
+
+    fn p(&self, symbol: &char) -> Option<&f64> {
+        self.map.get(symbol)
+    }
+
+    fn symbols(&self) -> Keys<'_, char, f64> {
+        self.map.keys()
+    }
+
+    fn hx(&self) -> f64 {
+        Distribution::hx(self)
+    }
+}
+
+impl<'a> IntoIterator for &'a Distribution {
+    type Item = (&'a char, &'a f64);
+    type IntoIter = std::collections::hash_map::Iter<'a, char, f64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
+    }
+}
+
+impl Default for Distribution {
+    /// distribution of English letters and space
+    fn default() -> Self {
+        let alphabets = [
+            'a', 'b', 'c', 'd', 'e',
+            'f', 'g', 'h', 'i', 'j',
+            'k', 'l', 'm', 'n', 'o',
+            'p', 'q', 'r', 's', 't',
+            'u', 'v', 'w', 'x', 'y',
+            'z', ' '
+        ];
+
+        // ref. http://www.macfreek.nl/memory/Letter_Distribution#Letter_Frequency
+        let distribution = [
+            0.06545420428810268, 0.012614349400134882, 0.022382079660795914, 0.032895839710101495, 0.10287480840814522,
+            0.019870906945619955, 0.01628201251975626, 0.0498866519336527, 0.05679944220647908, 0.0009771967640664421,
+            0.005621008826086285, 0.03324279082953061, 0.020306796250368523, 0.057236004874678816, 0.061720746945911634,
+            0.015073764715016882, 0.0008384527300266635, 0.049980287430261394, 0.05327793252372975, 0.07532249847431097,
+            0.022804128240333354, 0.007977317166161044, 0.017073508770571122, 0.0014120607927983009, 0.014305632773116854,
+            0.0005138874382474097, 0.18325568938199557];
+
+        let map = alphabets.iter().cloned()
+            .zip(distribution.iter().cloned())
+            .collect::<HashMap<_, _>>();
+
+        Self::with_map(map)
+    }
+}
+
+impl From<Frequencies> for Distribution {
+    fn from(frequencies: Frequencies) -> Self {
+        Self::new(frequencies)
+    }
+}
+
+impl TryFrom<&str> for Distribution {
+    type Error = TetError;
+
+    /// distribution of characters of the text, which must not be empty
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        if s.is_empty() {
+            Err(TetError::EmptyInput)
+        } else {
+            Ok(Self::from_text(s))
+        }
+    }
 }
 
+impl TryFrom<String> for Distribution {
+    type Error = TetError;
+
+    /// distribution of characters of the text, which must not be empty
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::try_from(s.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_frequencies_test() {
+        let mut frequencies = Frequencies::new();
+        "abca".chars().for_each(|c| frequencies.record(c));
+
+        let distribution = Distribution::from(frequencies.clone());
+
+        assert_eq!(distribution, Distribution::new(frequencies));
+        assert_eq!(distribution.p(&'a'), Some(&0.5));
+    }
+
+    #[cfg(feature = "serde1")]
+    #[test]
+    fn serde_statistics_test() {
+        let distribution = Distribution::with_map([('🚀', 0.75), ('a', 0.25)].iter().cloned().collect());
+
+        let json = serde_json::to_value(distribution.statistics()).unwrap();
+        assert_eq!(json["most_common"], serde_json::json!(["🚀", 0.75]));
+    }
+
+    #[cfg(feature = "serde1")]
+    #[test]
+    fn serde_non_bmp_test() {
+        let mut frequencies = Frequencies::new();
+        // a rocket (non-BMP) and characters adjacent to the surrogate range
+        "🚀🚀\u{d7ff}\u{e000}a".chars().for_each(|c| frequencies.record(c));
+
+        let json = serde_json::to_string(&frequencies).unwrap();
+        let deserialized: Frequencies = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, frequencies);
+
+        // escaped surrogate pairs are accepted as well
+        let json = r#"{"map":{"\ud83d\ude80":2}}"#;
+        let deserialized: Frequencies = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.map.get(&'🚀'), Some(&2));
+
+        let distribution = Distribution::new(frequencies);
+        let json = serde_json::to_string(&distribution).unwrap();
+        let deserialized: Distribution = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, distribution);
+    }
+
+    #[test]
+    fn merge_test() {
+        let mut frequencies = Frequencies::new();
+        frequencies.record_str("abca");
+
+        let mut other = Frequencies::new();
+        other.record_str("bcd");
+
+        frequencies.merge(&other);
+
+        let mut answer = Frequencies::new();
+        answer.record_str("abcabcd");
+
+        assert_eq!(frequencies, answer);
+        assert_eq!(frequencies.n(), 7);
+    }
+
+    #[test]
+    fn retain_class_test() {
+        let mut source = Frequencies::new();
+        source.record_str("Hello, World 42! うまぴょい");
+
+        let mut frequencies = source.clone();
+        frequencies.retain_alphabetic(false);
+        let mut answer = Frequencies::new();
+        answer.record_str("Hello World  うまぴょい");
+        assert_eq!(frequencies, answer);
+
+        let mut frequencies = source.clone();
+        frequencies.retain_alphabetic(true);
+        let mut answer = Frequencies::new();
+        answer.record_str("hello world  うまぴょい");
+        assert_eq!(frequencies, answer);
+
+        let mut frequencies = source.clone();
+        frequencies.retain_alphanumeric(true);
+        let mut answer = Frequencies::new();
+        answer.record_str("hello world 42 うまぴょい");
+        assert_eq!(frequencies, answer);
+
+        let mut frequencies = source;
+        frequencies.retain_ascii_printable(false);
+        let mut answer = Frequencies::new();
+        answer.record_str("Hello, World 42! ");
+        assert_eq!(frequencies, answer);
+    }
+
+    #[test]
+    fn update_from_text_test() {
+        let uniform = [('a', 0.25), ('b', 0.25), ('c', 0.25), ('d', 0.25)].iter().cloned().collect();
+        let mut distribution = Distribution::with_map(uniform);
+
+        // 4 pseudo-counts of the prior and 4 characters of the text
+        distribution.update_from_text("aaae", 4.0).unwrap();
+        assert_eq!(distribution.get('a'), Some(0.5));
+        assert_eq!(distribution.get('b'), Some(0.125));
+        assert_eq!(distribution.get('e'), Some(0.125));
+        assert!((distribution.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 0.00000000001);
+
+        assert_eq!(distribution.update_from_text("a", -1.0), Err(TetError::NegativeWeight(-1.0)));
+    }
+
+    #[test]
+    fn add_char_test() {
+        let mut distribution = Distribution::with_map([('a', 0.5), ('b', 0.5)].iter().cloned().collect());
+
+        distribution.add_char('c', 0.5).unwrap();
+        assert_eq!(distribution.get('a'), Some(0.25));
+        assert_eq!(distribution.get('c'), Some(0.5));
+
+        distribution.add_char('c', 0.0).unwrap();
+        assert_eq!(distribution.get('a'), Some(0.5));
+
+        assert_eq!(distribution.add_char('d', -0.1), Err(TetError::NegativeProbability('d')));
+        assert_eq!(distribution.add_char('d', 1.0), Err(TetError::ProbabilityTooLarge('d')));
+        assert_eq!(distribution.get('d'), None);
+
+        let mut distribution = Distribution::with_map([('a', 1.0)].iter().cloned().collect());
+        assert_eq!(distribution.add_char('a', 0.5), Err(TetError::EmptyDistribution));
+    }
+
+    #[test]
+    fn try_from_str_test() {
+        let distribution = Distribution::try_from("abca").unwrap();
+        assert_eq!(distribution.get('a'), Some(0.5));
+        assert_eq!(distribution.iter().map(|(_, p)| p).sum::<f64>(), 1.0);
+        assert_eq!(Distribution::try_from(String::from("abca")), Ok(distribution));
+
+        assert_eq!(Distribution::try_from(""), Err(TetError::EmptyInput));
+        assert_eq!(Distribution::try_from(String::new()), Err(TetError::EmptyInput));
+    }
+
+    #[test]
+    fn from_texts_test() {
+        let distribution = Distribution::from_texts(vec!["abca", "bcd"]);
+        assert_eq!(distribution, Distribution::from_text("abcabcd"));
+        assert_eq!(distribution.get('a'), Some(2.0 / 7.0));
+    }
+
+    #[test]
+    fn most_common_test() {
+        let mut frequencies = Frequencies::new();
+        frequencies.record_str("banana");
+
+        assert_eq!(frequencies.most_common(1), vec![('a', 3)]);
+        assert_eq!(frequencies.most_common(5), vec![('a', 3), ('n', 2), ('b', 1)]);
+        assert_eq!(frequencies.count('n'), 2);
+        assert_eq!(frequencies.count('z'), 0);
+        assert_eq!(frequencies.iter().map(|(_, n)| n).sum::<u128>(), frequencies.n());
+    }
+
+    #[test]
+    fn sum_test() {
+        let frequencies = ["abca", "bcd", "うまぴょい"].iter()
+            .map(|s| {
+                let mut frequencies = Frequencies::new();
+                frequencies.record_str(s);
+                frequencies
+            })
+            .collect::<Vec<_>>();
+
+        let mut merged = Frequencies::new();
+        frequencies.iter().for_each(|f| merged.merge(f));
+
+        assert_eq!(frequencies.iter().sum::<Frequencies>(), merged);
+        assert_eq!(frequencies[0].clone() + frequencies[1].clone() + frequencies[2].clone(), merged);
+        assert_eq!(frequencies.into_iter().sum::<Frequencies>(), merged);
+        assert_eq!(Vec::<Frequencies>::new().into_iter().sum::<Frequencies>(), Frequencies::new());
+    }
+
+    #[test]
+    fn from_line_results_test() {
+        use std::io::BufRead;
+
+        let reader = std::io::Cursor::new("the fox\nthe box\n");
+        let frequencies = Frequencies::from_line_results(reader.lines()).unwrap();
+        assert_eq!(frequencies.count('t'), 2);
+        assert_eq!(frequencies.count('\n'), 0);
+        assert_eq!(frequencies.n(), 14);
+
+        let lines = vec![
+            Ok("the fox".to_string()),
+            Err(io::Error::new(io::ErrorKind::InvalidData, "broken line")),
+            Ok("the box".to_string()),
+        ];
+        let error = Frequencies::from_line_results(lines).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "broken line");
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn from_par_chunks_test() {
+        let corpus = "large and appropriate text is recommended. うまぴょい";
+
+        let mut sequential = Frequencies::new();
+        sequential.record_str(corpus);
+
+        let chunks = corpus.split_inclusive(' ').collect::<Vec<_>>();
+        assert_eq!(Frequencies::from_par_chunks(&chunks), sequential);
+        assert_eq!(Frequencies::from_par_chunks(&[]), Frequencies::new());
+    }
+
+    #[test]
+    fn get_test() {
+        let map = [('a', 0.25), ('b', 0.75)].iter().cloned().collect::<HashMap<_, _>>();
+        let distribution = Distribution::with_map(map);
+
+        assert_eq!(distribution.get('b'), Some(0.75));
+        assert_eq!(distribution.get('c'), None);
+        assert!(distribution.contains('a'));
+        assert!(!distribution.contains('c'));
+    }
+
+    #[test]
+    fn eq_ignoring_zeros_test() {
+        let distribution = Distribution::with_map([('a', 0.25), ('b', 0.75)].iter().cloned().collect());
+        let with_zero = Distribution::with_map([('a', 0.25), ('b', 0.75), ('x', 0.0)].iter().cloned().collect());
+
+        assert_ne!(distribution, with_zero);
+        assert!(distribution.eq_ignoring_zeros(&with_zero, 1e-12));
+        assert!(with_zero.eq_ignoring_zeros(&distribution, 1e-12));
+
+        let other = Distribution::with_map([('a', 0.5), ('b', 0.5)].iter().cloned().collect());
+        assert!(!distribution.eq_ignoring_zeros(&other, 1e-12));
+        let missing = Distribution::with_map([('a', 0.25)].iter().cloned().collect());
+        assert!(!distribution.eq_ignoring_zeros(&missing, 1e-12));
+    }
+
+    #[test]
+    fn from_counts_test() {
+        let distribution = Distribution::from_counts(&[('a', 1), ('a', 1), ('b', 2)]).unwrap();
+        let expected = Distribution::with_map([('a', 0.5), ('b', 0.5)].iter().cloned().collect());
+        assert_eq!(distribution, expected);
+
+        assert_eq!(Distribution::from_counts(&[]), Err(TetError::EmptyDistribution));
+        assert_eq!(Distribution::from_counts(&[('a', 0), ('b', 0)]), Err(TetError::EmptyDistribution));
+    }
+
+    #[test]
+    fn missing_chars_test() {
+        let distribution = Distribution::default();
+
+        assert_eq!(distribution.missing_chars("abc123"), vec!['1', '2', '3']);
+        assert_eq!(distribution.missing_chars("3a2b3c1"), vec!['3', '2', '1']);
+        assert!(distribution.missing_chars("the fox").is_empty());
+    }
+
+    #[test]
+    fn covers_test() {
+        // alphabet letter distribution
+        let distribution = Distribution::default();
+        let mut required = ('a'..='z').collect::<Vec<_>>();
+        required.push(' ');
+
+        assert_eq!(distribution.covers(&required), Ok(()));
+
+        let without_q = required.iter().cloned().filter(|&c| c != 'q').collect::<Vec<_>>();
+        assert_eq!(
+            distribution.covers(&without_q),
+            Err(CoverageError { missing: vec![], extra: vec!['q'] })
+        );
+
+        assert_eq!(
+            Distribution::from_text("bca").covers(&['a', 'b', '1']),
+            Err(CoverageError { missing: vec!['1'], extra: vec!['c'] })
+        );
+    }
+
+    #[test]
+    fn combine_test() {
+        let first = Distribution::with_map([('a', 0.5), ('b', 0.5)].iter().cloned().collect());
+        let second = Distribution::with_map([('a', 0.25), ('c', 0.75)].iter().cloned().collect());
+
+        let combined = Distribution::combine(&[(first, 3.0), (second, 1.0)]).unwrap();
+
+        // (3 * 0.5 + 1 * 0.25) / 4
+        assert_eq!(combined.get('a'), Some(0.4375));
+        assert_eq!(combined.get('b'), Some(0.375));
+        assert_eq!(combined.get('c'), Some(0.1875));
+
+        let first = Distribution::with_map([('a', 1.0)].iter().cloned().collect());
+        let second = Distribution::with_map([('b', 1.0)].iter().cloned().collect());
+        assert_eq!(
+            Distribution::combine(&[(first, -1.0), (second, 1.0)]),
+            Err(TetError::NegativeWeight(-1.0))
+        );
+
+        let first = Distribution::with_map([('a', 1.0)].iter().cloned().collect());
+        assert_eq!(Distribution::combine(&[(first, 0.0)]), Err(TetError::EmptyDistribution));
+        assert_eq!(Distribution::combine(&[]), Err(TetError::EmptyDistribution));
+    }
+
+    #[test]
+    fn difference_test() {
+        let distribution = Distribution::default();
+        let difference = distribution.difference(&distribution);
+        assert_eq!(difference.len(), 27);
+        assert!(difference.iter().all(|&(_, d)| d == 0.0));
+
+        let first = Distribution::with_map([('a', 0.5), ('b', 0.5)].iter().cloned().collect());
+        let second = Distribution::with_map([('a', 0.25), ('b', 0.5), ('c', 0.25)].iter().cloned().collect());
+        assert_eq!(first.difference(&second), vec![('a', 0.25), ('c', -0.25), ('b', 0.0)]);
+        assert_eq!(second.difference(&first), vec![('a', -0.25), ('c', 0.25), ('b', 0.0)]);
+    }
+
+    #[test]
+    fn entropy_contributions_test() {
+        let distribution = Distribution::default();
+        let contributions = distribution.entropy_contributions();
+
+        assert_eq!(contributions.len(), 27);
+        assert_eq!(contributions[0].0, ' ');
+        assert!(contributions.windows(2).all(|w| w[0].1 >= w[1].1));
+        let sum = contributions.iter().map(|(_, h)| h).sum::<f64>();
+        assert!((sum - distribution.hx()).abs() < 0.00000000001);
+
+        let distribution = Distribution::with_map([('a', 0.5), ('b', 0.5), ('c', 0.0)].iter().cloned().collect());
+        assert_eq!(distribution.entropy_contributions(), vec![('a', 0.5), ('b', 0.5), ('c', 0.0)]);
+    }
+
+    #[test]
+    fn renyi_entropy_test() {
+        let distribution = Distribution::default();
+        let hx = distribution.hx();
+
+        assert!((distribution.renyi_entropy(0.0) - 27f64.log2()).abs() < 0.00000000001);
+        assert_eq!(distribution.renyi_entropy(1.0), hx);
+        assert!(distribution.renyi_entropy(2.0) < hx);
+        assert!(distribution.renyi_entropy(0.5) > hx);
+        assert!(distribution.renyi_entropy(-1.0).is_nan());
+        assert!(distribution.renyi_entropy(f64::NAN).is_nan());
+
+        // min-entropy, the limit of large orders
+        let min_entropy = distribution.renyi_entropy(f64::INFINITY);
+        assert!((min_entropy + 0.18325568938199557f64.log2()).abs() < 0.00000000001);
+        assert!(distribution.renyi_entropy(100.0) > min_entropy);
+        assert!(distribution.renyi_entropy(100.0) - min_entropy < 0.1);
+
+        // continuous around order 1
+        assert!((distribution.renyi_entropy(1.0 - 1e-9) - hx).abs() < 0.000001);
+        assert!((distribution.renyi_entropy(1.0 + 1e-9) - hx).abs() < 0.000001);
+
+        // uniform distributions have the same entropy of every order
+        let uniform = Distribution::with_map([('a', 0.25), ('b', 0.25), ('c', 0.25), ('d', 0.25), ('e', 0.0)].iter().cloned().collect());
+        assert!((uniform.renyi_entropy(0.0) - 2.0).abs() < 0.00000000001);
+        assert!((uniform.renyi_entropy(2.0) - 2.0).abs() < 0.00000000001);
+        assert!((uniform.renyi_entropy(f64::INFINITY) - 2.0).abs() < 0.00000000001);
+    }
+
+    #[test]
+    fn entropy_max_test() {
+        let distribution = Distribution::with_map([('a', 1.0)].iter().cloned().collect());
+        assert_eq!(distribution.entropy_max(), 0.0);
+
+        let distribution = Distribution::with_map(HashMap::new());
+        assert_eq!(distribution.entropy_max(), 0.0);
+    }
+
+    #[test]
+    fn most_common_char_test() {
+        let distribution = Distribution::default();
+        assert_eq!(distribution.most_common_char(), Some((' ', 0.18325568938199557)));
+        assert_eq!(distribution.probability_of(' '), 0.18325568938199557);
+        assert_eq!(distribution.probability_of('1'), 0.0);
+
+        let distribution = Distribution::with_map([('b', 0.5), ('a', 0.5)].iter().cloned().collect());
+        assert_eq!(distribution.most_common_char(), Some(('a', 0.5)));
+        assert_eq!(Distribution::with_map(HashMap::new()).most_common_char(), None);
+    }
+
+    #[test]
+    fn statistics_test() {
+        let distribution = Distribution::with_map([('a', 0.5), ('b', 0.5)].iter().cloned().collect());
+        let stats = distribution.statistics();
+        assert_eq!(stats.perplexity, 2.0);
+        assert_eq!(stats.redundancy, 0.0);
+        assert_eq!(stats.most_common, Some(('a', 0.5)));
+
+        let stats = Distribution::with_map(HashMap::new()).statistics();
+        assert_eq!(stats.size, 0);
+        assert_eq!(stats.redundancy, 0.0);
+        assert_eq!(stats.most_common, None);
+    }
+
+    #[test]
+    fn normalized_test() {
+        let map = [('a', 3.0), ('b', 1.0)].iter().cloned().collect::<HashMap<_, _>>();
+        let distribution = Distribution::normalized(map).unwrap();
+        assert_eq!(distribution.p(&'a'), Some(&0.75));
+
+        let map = [('a', 0.5), ('b', -0.5)].iter().cloned().collect::<HashMap<_, _>>();
+        assert_eq!(Distribution::normalized(map), Err(TetError::NegativeProbability('b')));
+
+        let map = [('a', 0.0), ('b', 0.0)].iter().cloned().collect::<HashMap<_, _>>();
+        assert_eq!(Distribution::normalized(map), Err(TetError::EmptyDistribution));
+    }
+
+    #[test]
+    fn text_cross_entropy_test() {
+        let distribution = Distribution::default();
+
+        // a text of the same frequencies as the distribution
+        let text = distribution.iter()
+            .map(|(c, p)| c.to_string().repeat((p * 100000.0).round() as usize))
+            .collect::<String>();
+        let cross_entropy = distribution.text_cross_entropy(&text).unwrap();
+        assert!((cross_entropy - distribution.hx()).abs() < 0.001);
+
+        assert_eq!(
+            distribution.text_cross_entropy("what's up, doc?"),
+            Err(TetError::UnknownCharacters(vec!['\'', ',', '?'])),
+        );
+        assert_eq!(distribution.text_cross_entropy(""), Err(TetError::EmptyInput));
+    }
+
+    #[test]
+    fn with_temperature_test() {
+        let distribution = Distribution::default();
+
+        let same = distribution.with_temperature(1.0).unwrap();
+        for (c, p) in distribution.iter() {
+            assert!((same.get(*c).unwrap() - p).abs() < 0.00000000001);
+        }
+
+        let flat = distribution.with_temperature(1e6).unwrap();
+        assert!((flat.hx() - 27f64.log2()).abs() < 0.0001);
+
+        let sharp = distribution.with_temperature(1e-3).unwrap();
+        assert!(sharp.hx() < 0.0001);
+        assert!(sharp.get(' ').unwrap() > 0.9999);
+
+        assert_eq!(distribution.with_temperature(0.0), Err(TetError::InvalidTemperature(0.0)));
+    }
+
+    #[test]
+    fn as_map_test() {
+        let distribution = Distribution::default();
+
+        assert_eq!(distribution.as_map().len(), 27);
+        assert_eq!(distribution.as_map().get(&' '), Some(&0.18325568938199557));
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+/// distribution of adjacent pairs of characters
+///
+/// Used as a source of context-aware entropy,
+/// see [`TextEntryThroughput::with_context`](crate::TextEntryThroughput::with_context).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BigramDistribution {
+    /// counts of pairs of a character and the next one
+    pairs: HashMap<(char, char), u128>,
+    /// counts of characters followed by another
+    previous: HashMap<char, u128>,
+}
+
+impl BigramDistribution {
+    /// count adjacent pairs of characters of the text
+    pub fn from_text(text: &str) -> Self {
+        let mut distribution = Self::default();
+        distribution.record_str(text);
+
+        distribution
+    }
+
+    /// count adjacent pairs of characters of the text, but not across texts
+    pub fn record_str(&mut self, text: &str) {
+        let chars = text.chars().collect::<Vec<_>>();
+        for pair in chars.windows(2) {
+            *self.pairs.entry((pair[0], pair[1])).or_insert(0) += 1;
+            *self.previous.entry(pair[0]).or_insert(0) += 1;
+        }
+    }
+
+    /// characters of counted pairs, in arbitrary order
+    pub fn chars(&self) -> impl Iterator<Item=char> + '_ {
+        self.pairs.keys()
+            .flat_map(|&(a, b)| [a, b])
+            .collect::<std::collections::HashSet<_>>()
+            .into_iter()
+    }
+
+    /// number of counted pairs
+    pub fn n(&self) -> u128 {
+        self.previous.values().sum()
+    }
+
+    /// H(X_n | X_{n-1}): conditional entropy of a character given the previous one (bits/character)
+    ///
+    /// It is 0 without pairs.
+    pub fn hx(&self) -> f64 {
+        let n = self.n() as f64;
+
+        -self.pairs.iter()
+            .map(|((a, _), &count)| {
+                // p(a, b) log p(b | a)
+                let p_ab = count as f64 / n;
+                p_ab * (count as f64 / self.previous[a] as f64).log2()
+            })
+            .sum::<f64>()
+    }
+}
+
+/// entropy rate of the text estimated from its bigrams (bits/character)
+///
+/// H(X_n | X_{n-1}): conditional entropy of a character given the previous one,
+/// with probabilities counted over adjacent pairs of characters of the text.
+/// It is 0 for a text of less than 2 characters.
+pub fn bigram_entropy(text: &str) -> f64 {
+    BigramDistribution::from_text(text).hx()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bigram_entropy_test() {
+        let repetitive = bigram_entropy("ababab");
+        let random = bigram_entropy("abbabaaabbbaabab");
+
+        assert_eq!(repetitive, 0.0);
+        assert!(repetitive < random);
+
+        assert_eq!(bigram_entropy("a"), 0.0);
+        assert_eq!(bigram_entropy(""), 0.0);
+
+        // each character is followed by 'a' or 'b' equally
+        assert!((bigram_entropy("aabba") - 1.0).abs() < 0.00000000001);
+    }
+
+    #[test]
+    fn bigram_distribution_test() {
+        let mut distribution = BigramDistribution::from_text("aab");
+        distribution.record_str("ba");
+        assert_eq!(distribution.n(), 3);
+        // 'b' of "aab" and "ba" are not paired, so 'b' is always followed by 'a'
+        assert!((distribution.hx() - 2.0 / 3.0).abs() < 0.00000000001);
+        assert!(distribution.hx() < bigram_entropy("aabba"));
+
+        assert_eq!(BigramDistribution::default().hx(), 0.0);
+    }
+}
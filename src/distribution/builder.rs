@@ -0,0 +1,119 @@
+use crate::distribution::Distribution;
+use crate::error::TetError;
+
+/// builder of a mixture of distributions of named texts with weights
+///
+/// ```
+/// use tet_rs::DistributionBuilder;
+///
+/// let distribution = DistributionBuilder::new()
+///     .add_source("english", "large and appropriate text", 3.0)
+///     .add_source("glossary", "throughput", 1.0)
+///     .build()
+///     .unwrap();
+///
+/// assert!(distribution.contains('h'));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DistributionBuilder {
+    sources: Vec<(String, Distribution, f64)>,
+}
+
+impl DistributionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add the distribution of characters of the text with the weight
+    pub fn add_source(mut self, name: &str, text: &str, weight: f64) -> Self {
+        self.sources.push((name.to_string(), Distribution::from_text(text), weight));
+        self
+    }
+
+    /// share of each source in the mixture, in order of addition
+    ///
+    /// Shares are weights normalized over non-empty sources, and 0 for empty sources.
+    /// Fails like [`build`](Self::build) if no source with a positive weight has a character.
+    pub fn contributions(&self) -> Result<Vec<(String, f64)>, TetError> {
+        self.validate()?;
+
+        let total = self.sources.iter()
+            .filter(|(_, distribution, _)| distribution.iter().next().is_some())
+            .map(|(_, _, weight)| weight)
+            .sum::<f64>();
+
+        if total <= 0.0 {
+            return Err(TetError::EmptyDistribution);
+        }
+
+        Ok(self.sources.iter()
+            .map(|(name, distribution, weight)| {
+                let share = if distribution.iter().next().is_some() { weight / total } else { 0.0 };
+                (name.clone(), share)
+            })
+            .collect())
+    }
+
+    /// mixture of sources weighted and renormalized
+    ///
+    /// Fails if a weight is negative, or no source with a positive weight has a character.
+    pub fn build(&self) -> Result<Distribution, TetError> {
+        self.validate()?;
+
+        let parts = self.sources.iter()
+            .map(|(_, distribution, weight)| (distribution.clone(), *weight))
+            .collect::<Vec<_>>();
+
+        Distribution::combine(&parts)
+    }
+
+    fn validate(&self) -> Result<(), TetError> {
+        match self.sources.iter().find(|(_, _, w)| w.is_nan() || *w < 0.0) {
+            Some((_, _, w)) => Err(TetError::NegativeWeight(*w)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn build_test() {
+        let builder = DistributionBuilder::new()
+            .add_source("first", "ab", 2.0)
+            .add_source("second", "bc", 1.0)
+            .add_source("empty", "", 5.0);
+
+        let distribution = builder.build().unwrap();
+        // 'a' only in the first source: 1/2 * 2/3
+        assert!((distribution.get('a').unwrap() - 1.0 / 3.0).abs() < 0.00000000001);
+        assert!((distribution.get('b').unwrap() - 1.0 / 2.0).abs() < 0.00000000001);
+        assert!((distribution.get('c').unwrap() - 1.0 / 6.0).abs() < 0.00000000001);
+
+        let contributions = builder.contributions().unwrap();
+        assert_eq!(contributions[0].0, "first");
+        assert!((contributions[0].1 - 2.0 / 3.0).abs() < 0.00000000001);
+        assert!((contributions[1].1 - 1.0 / 3.0).abs() < 0.00000000001);
+        assert_eq!(contributions[2].1, 0.0);
+    }
+
+    #[test]
+    fn invalid_sources_test() {
+        let negative = DistributionBuilder::new().add_source("negative", "ab", -1.0);
+        assert_eq!(negative.build(), Err(TetError::NegativeWeight(-1.0)));
+        assert_eq!(negative.contributions(), Err(TetError::NegativeWeight(-1.0)));
+
+        let empty = DistributionBuilder::new().add_source("empty", "", 1.0);
+        assert_eq!(empty.build(), Err(TetError::EmptyDistribution));
+        assert_eq!(empty.contributions(), Err(TetError::EmptyDistribution));
+        assert_eq!(DistributionBuilder::new().build(), Err(TetError::EmptyDistribution));
+
+        let zero = DistributionBuilder::new()
+            .add_source("first", "ab", 0.0)
+            .add_source("second", "bc", 0.0);
+        assert_eq!(zero.build(), Err(TetError::EmptyDistribution));
+        assert_eq!(zero.contributions(), Err(TetError::EmptyDistribution));
+    }
+}
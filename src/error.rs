@@ -0,0 +1,125 @@
+use std::fmt;
+
+/// errors of text entry throughput
+#[derive(Debug, Clone, PartialEq)]
+pub enum TetError {
+    /// a probability of the character is negative (or not a number)
+    NegativeProbability(char),
+    /// a probability of the character is 1 or more
+    ProbabilityTooLarge(char),
+    /// no character has a positive probability
+    EmptyDistribution,
+    /// characters are not in the distribution
+    UnknownCharacters(Vec<char>),
+    /// a text is empty
+    EmptyInput,
+    /// a temperature is not positive
+    InvalidTemperature(f64),
+    /// a weight is negative (or not a number)
+    NegativeWeight(f64),
+    /// mutual information is undefined for the texts
+    UndefinedInformation,
+    /// a trial ends before it starts
+    EndBeforeStart,
+    /// no time is spent for entry
+    ZeroDuration,
+    /// bytes are not valid UTF-8
+    InvalidUtf8,
+    /// rows of an alignment differ in length, or NULL is aligned with NULL
+    InvalidAlignment,
+    /// no reference text is given
+    NoReference,
+    /// a trial log cannot be parsed
+    InvalidLog(String),
+    /// texts are too long to align within the limit of matrix cells
+    InputTooLong { limit: usize },
+    /// error rates are out of [0, 1] or do not sum up to 1
+    InvalidErrorRates,
+    /// words per minute are not positive and finite, or the time of entry overflows
+    InvalidWpm(f64),
+    /// a cost of an edit operation is negative or not finite
+    InvalidCost(f64),
+}
+
+/// mismatch between characters of a distribution and the required ones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageError {
+    /// required characters absent from the distribution, in the required order
+    pub missing: Vec<char>,
+    /// characters of the distribution not required, in the order of code points
+    pub extra: Vec<char>,
+}
+
+/// result with [`TetError`](TetError)
+pub type Result<T, E = TetError> = std::result::Result<T, E>;
+
+impl fmt::Display for TetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TetError::NegativeProbability(c) => {
+                write!(f, "probability of {:?} is negative", c)
+            }
+            TetError::ProbabilityTooLarge(c) => {
+                write!(f, "probability of {:?} is 1 or more", c)
+            }
+            TetError::EmptyDistribution => {
+                write!(f, "distribution has no positive probability")
+            }
+            TetError::UnknownCharacters(cs) => {
+                write!(f, "characters {:?} are not in the distribution", cs)
+            }
+            TetError::EmptyInput => {
+                write!(f, "text is empty")
+            }
+            TetError::InvalidTemperature(t) => {
+                write!(f, "temperature {} is not positive", t)
+            }
+            TetError::NegativeWeight(w) => {
+                write!(f, "weight {} is negative", w)
+            }
+            TetError::UndefinedInformation => {
+                write!(f, "mutual information is undefined for the texts")
+            }
+            TetError::EndBeforeStart => {
+                write!(f, "trial ends before it starts")
+            }
+            TetError::ZeroDuration => {
+                write!(f, "no time is spent for entry")
+            }
+            TetError::InvalidUtf8 => {
+                write!(f, "bytes are not valid UTF-8")
+            }
+            TetError::InvalidAlignment => {
+                write!(f, "alignment is malformed")
+            }
+            TetError::NoReference => {
+                write!(f, "no reference text is given")
+            }
+            TetError::InvalidLog(message) => {
+                write!(f, "invalid trial log: {}", message)
+            }
+            TetError::InputTooLong { limit } => {
+                write!(f, "texts need more than {} cells to align", limit)
+            }
+            TetError::InvalidErrorRates => {
+                write!(f, "error rates must be in [0, 1] and sum up to 1")
+            }
+            TetError::InvalidWpm(wpm) => {
+                write!(f, "words per minute {} is not positive and finite, or too small", wpm)
+            }
+            TetError::InvalidCost(cost) => {
+                write!(f, "cost {} is negative or not finite", cost)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TetError {}
+
+impl fmt::Display for CoverageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "characters {:?} are missing and {:?} are extra", self.missing, self.extra)
+    }
+}
+
+impl std::error::Error for CoverageError {}
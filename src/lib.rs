@@ -28,8 +28,9 @@
 //!
 //! let mut frequency = Frequencies::new();
 //!
+//! // every character of presented and transcribed texts must appear in the source
 //! // get frequency of each character
-//! let source = "large and appropriate text is recommended";
+//! let source = "large and appropriate text is recommended, うまぴょい うまぽい";
 //! source.chars()
 //!     .for_each(|c| {
 //!         frequency.record(c.clone());
@@ -46,7 +47,7 @@
 //! # let mut frequency = Frequencies::new();
 //! #
 //! # // get frequency of each character
-//! # let source = "large and appropriate text is recommended";
+//! # let source = "large and appropriate text is recommended, うまぴょい うまぽい";
 //! # source.chars()
 //! #     .for_each(|c| {
 //! #         frequency.record(c.clone());
@@ -68,89 +69,2327 @@
 //! ```
 //!
 //! ## Features
-//! `serde1` feature allows you to save and load [`Frequencies`](Frequencies)
-//! and [`Distribution`](Distribution) via JSON.
+//! `serde1` feature allows you to save and load [`Frequencies`](Frequencies),
+//! [`Distribution`](Distribution) and [`TetReport`](TetReport) (with its alignments) via JSON.
 //!
 //! ```toml: Cargo.toml
 //! tet = { version = "0.1", features = ["serde1"] }
 //! ```
+//!
+//! `tracing` feature emits [`tracing`](https://docs.rs/tracing) events of intermediate values
+//! (edit distance, number of optimal alignments, component probabilities, `H_Y(X)`).
+//!
+//! `rayon` feature enables parallel computation such as
+//! [`Frequencies::from_par_chunks`](Frequencies::from_par_chunks).
+//!
+//! `unicode-normalization` feature enables normalizing texts into NFC before alignment
+//! with [`TextEntryThroughput::nfc`](TextEntryThroughput::nfc),
+//! and ignoring case and accents with
+//! [`TextEntryThroughput::fold_case_and_accents`](TextEntryThroughput::fold_case_and_accents).
+//!
+//! `csv` feature enables loading trials from study logs
+//! with [`load_trials_csv`](load_trials_csv).
+//!
+//! `sim` feature enables resampling statistics such as
+//! [`Session::bootstrap_ci`](Session::bootstrap_ci).
 
-pub use crate::distribution::{Distribution, Frequencies};
+pub use crate::confusion::ConfusionMatrix;
+pub use crate::consensus::consensus_reference;
+pub use crate::cost::{Cost, ProbabilityAwareCost};
+pub use crate::counter::FrequencyCounter;
+pub use crate::distribution::{
+    bigram_entropy, BigramDistribution, Distribution, DistributionBuilder, DistributionStats, Frequencies, Source,
+};
+pub use crate::error::{CoverageError, Result, TetError};
+pub use crate::optimal_alignments::{
+    Alignment, AlignmentOptions, Element, ErrorRates, Intermediates, NullEstimator, Operation, OptimalAlignments,
+    RateNormalization, Selection, SubstitutionModel, TetScratch,
+};
+pub use crate::report::TetReport;
+pub use crate::session::{GroupedSession, Session, SessionSummary};
+pub use crate::statistics::{compare, Comparison};
+#[cfg(feature = "csv")]
+pub use crate::studies::load_trials_csv;
+pub use crate::studies::{from_dir, from_dir_with, IncompleteTrials, Trial};
+pub use crate::word::{WordDistribution, WordTextEntryThroughput};
+use crate::optimal_alignments::{Channel, Costs};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::Arc;
 
+mod confusion;
+mod consensus;
+mod cost;
+mod counter;
 mod distribution;
+mod error;
 mod optimal_alignments;
+mod report;
+mod session;
+mod statistics;
+mod studies;
+mod word;
+
+/// unit of time of a throughput
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rate {
+    /// bits/s, as reported in the paper (default)
+    #[default]
+    PerSecond,
+    /// bits/min
+    PerMinute,
+}
+
+impl Rate {
+    /// seconds per the unit of time
+    fn seconds(self) -> f64 {
+        match self {
+            Rate::PerSecond => 1.0,
+            Rate::PerMinute => 60.0,
+        }
+    }
+}
+
+/// characters counted as entered in a trial, i.e. the numerator of characters per second
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeBasis {
+    /// transcribed characters, as in the paper (default)
+    ///
+    /// The time is spent to enter the transcribed text.
+    #[default]
+    TranscribedChars,
+    /// length of the alignment, i.e. transcribed characters and omissions
+    ///
+    /// An omitted character is handled in the time as well, like a skipped key.
+    AlignedLength,
+    /// presented characters
+    ///
+    /// Trials of the same presented text are compared at the same number of characters,
+    /// regardless of how many characters were typed.
+    PresentedChars,
+}
+
+/// context of characters taken into account for the entropy of the source
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Context {
+    /// characters are independent, as in the paper (default)
+    #[default]
+    Unigram,
+    /// a character depends on the previous one
+    ///
+    /// H(X) is replaced by H(X_n | X_{n-1}) of the bigram distribution,
+    /// while the channel model of errors is still of the character distribution.
+    /// Estimated from a small corpus, H(X_n | X_{n-1}) can fall below H_Y(X),
+    /// and then I(X,Y) is clamped at 0.
+    Bigram(BigramDistribution),
+}
+
+/// handling of control characters absent from the distribution (e.g. `\0`, `\r`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SanitizePolicy {
+    /// leave them, so that they fail as unknown characters (default)
+    #[default]
+    Reject,
+    /// remove them
+    Strip,
+    /// replace them with the character
+    Replace(char),
+}
 
+#[derive(Debug, Clone)]
 pub struct TextEntryThroughput {
-    distribution: Distribution
+    distribution: Arc<Distribution>,
+    /// distribution as given, before characters are merged or folded
+    source: Arc<Distribution>,
+    options: AlignmentOptions,
+    average_alignments: bool,
+    max_alignments: Option<usize>,
+    collapse_whitespace: bool,
+    excluded_chars: Vec<char>,
+    equivalence_classes: Vec<Vec<char>>,
+    sanitize: SanitizePolicy,
+    time_basis: TimeBasis,
+    context: Context,
+    #[cfg(feature = "unicode-normalization")]
+    nfc: bool,
+    #[cfg(feature = "unicode-normalization")]
+    fold_case_and_accents: bool,
 }
 
 impl TextEntryThroughput {
     pub fn new(distribution: Distribution) -> Self {
-        Self { distribution }
+        Self::from_shared(Arc::new(distribution))
+    }
+
+    /// share a read-only distribution among instances (e.g. across threads)
+    pub fn from_shared(distribution: Arc<Distribution>) -> Self {
+        Self {
+            source: distribution.clone(),
+            distribution,
+            options: AlignmentOptions::default(),
+            average_alignments: false,
+            max_alignments: None,
+            collapse_whitespace: false,
+            excluded_chars: Vec::new(),
+            equivalence_classes: Vec::new(),
+            sanitize: SanitizePolicy::default(),
+            time_basis: TimeBasis::default(),
+            context: Context::default(),
+            #[cfg(feature = "unicode-normalization")]
+            nfc: false,
+            #[cfg(feature = "unicode-normalization")]
+            fold_case_and_accents: false,
+        }
+    }
+
+    /// set an estimator of p(NULL)
+    pub fn null_estimator(mut self, null_estimator: NullEstimator) -> Self {
+        self.options.null_estimator = null_estimator;
+        self
+    }
+
+    /// set a floor of probabilities taken logarithms of (default: 0)
+    ///
+    /// See [`OptimalAlignments::min_probability`](OptimalAlignments::min_probability).
+    pub fn min_probability(mut self, min_probability: f64) -> Self {
+        self.options.min_probability = min_probability;
+        self
+    }
+
+    /// set a distribution of substitutions over characters (default: uniform)
+    ///
+    /// See [`SubstitutionModel`](SubstitutionModel).
+    pub fn substitution_model(mut self, substitution_model: SubstitutionModel) -> Self {
+        self.options.substitution_model = substitution_model;
+        self
+    }
+
+    /// set a basis of the denominators of reported component probabilities
+    ///
+    /// Throughput is computed from the paper's probabilities regardless of it.
+    /// See [`RateNormalization`](RateNormalization).
+    pub fn rate_normalization(mut self, rate_normalization: RateNormalization) -> Self {
+        self.options.rate_normalization = rate_normalization;
+        self
+    }
+
+    /// set a selection of an alignment among optimal alignments
+    pub fn selection(mut self, selection: Selection) -> Self {
+        self.options.selection = selection;
+        self
+    }
+
+    /// set a limit of `presented.len() * transcribed.len()` (default: 10,000,000)
+    ///
+    /// See [`AlignmentOptions::max_cells`](AlignmentOptions::max_cells).
+    pub fn max_cells(mut self, max_cells: usize) -> Self {
+        self.options.max_cells = max_cells;
+        self
+    }
+
+    /// set costs of a substitution and of an insertion or an omission (default: 1 and 1)
+    ///
+    /// A larger gap cost makes alignments prefer substitutions to pairs of insertion and omission.
+    pub fn costs(mut self, substitution: u32, gap: u32) -> Self {
+        self.options.substitution_cost = substitution;
+        self.options.gap_cost = gap;
+        self
+    }
+
+    /// set whether I(X,Y) is averaged over all optimal alignments (default: false)
+    ///
+    /// When averaging, every optimal alignment is evaluated and the selection is ignored.
+    pub fn average_alignments(mut self, average_alignments: bool) -> Self {
+        self.average_alignments = average_alignments;
+        self
+    }
+
+    /// set a limit of optimal alignments to average over (default: no limit)
+    ///
+    /// Some texts have combinatorially many optimal alignments.
+    /// Beyond the limit, only the alignment preferring diagonal moves
+    /// (see [`Selection::PreferDiagonal`](Selection::PreferDiagonal)) is evaluated
+    /// regardless of the selection, since finding the last one enumerates all of them.
+    /// This is reported by [`TetReport::truncated_alignments`](TetReport::truncated_alignments).
+    pub fn max_alignments(mut self, max_alignments: usize) -> Self {
+        self.max_alignments = Some(max_alignments);
+        self
+    }
+
+    /// set whether runs of whitespace are collapsed into a single space
+    /// and both ends are trimmed before alignment (default: false)
+    pub fn collapse_whitespace(mut self, collapse_whitespace: bool) -> Self {
+        self.collapse_whitespace = collapse_whitespace;
+        self
+    }
+
+    /// set whether texts are normalized into Unicode NFC before alignment (default: false)
+    ///
+    /// Precomposed and decomposed forms of a character (e.g. `é`) are then aligned as a correct entry.
+    /// Characters of the distribution should be in NFC as well.
+    #[cfg(feature = "unicode-normalization")]
+    pub fn nfc(mut self, nfc: bool) -> Self {
+        self.nfc = nfc;
+        self
+    }
+
+    /// set whether case and accents are ignored (default: false)
+    ///
+    /// Texts are lowercased, decomposed into NFD and stripped of combining marks,
+    /// so that e.g. `Café` and `cafe` are aligned as correct entries.
+    /// Characters of the distribution are folded in the same way
+    /// and probabilities of characters folded together are summed.
+    /// Fails if no character of the distribution is left after folding.
+    #[cfg(feature = "unicode-normalization")]
+    pub fn fold_case_and_accents(mut self, fold_case_and_accents: bool) -> Result<Self> {
+        self.fold_case_and_accents = fold_case_and_accents;
+        self.distribution = self.derive_distribution()?;
+
+        Ok(self)
+    }
+
+    /// ignore characters entirely (e.g. punctuation)
+    ///
+    /// The characters are removed from both presented and transcribed texts before alignment,
+    /// and from the distribution, whose probabilities are renormalized over the rest.
+    pub fn with_excluded_chars(&self, chars: &[char]) -> Self {
+        let mut distribution = Distribution::clone(&self.distribution);
+        distribution.retain(|c| !chars.contains(c));
+        let mut source = Distribution::clone(&self.source);
+        source.retain(|c| !chars.contains(c));
+
+        let mut excluded_chars = self.excluded_chars.clone();
+        excluded_chars.extend_from_slice(chars);
+
+        Self {
+            distribution: Arc::new(distribution),
+            source: Arc::new(source),
+            excluded_chars,
+            ..self.clone()
+        }
+    }
+
+    /// set classes of characters considered equal (e.g. homoglyphs `['0', 'O']`)
+    ///
+    /// Each character of a class is replaced by the first one of the class before alignment,
+    /// so that typing a character for another of its class costs nothing and counts as a correct entry.
+    /// In the distribution, the first character of a class takes over the probabilities of the others
+    /// (summed up). Classes replace the ones set before, whose characters are separated again.
+    pub fn equivalence_classes(mut self, equivalence_classes: Vec<Vec<char>>) -> Result<Self> {
+        self.equivalence_classes = equivalence_classes;
+        self.distribution = self.derive_distribution()?;
+
+        Ok(self)
+    }
+
+    /// the distribution as given, with characters of equivalence classes merged and then folded
+    fn derive_distribution(&self) -> Result<Arc<Distribution>> {
+        let classes = self.equivalence_classes.iter()
+            .filter(|class| !class.is_empty())
+            .collect::<Vec<_>>();
+        let distribution = if classes.is_empty() {
+            self.source.clone()
+        } else {
+            let mut map = self.source.as_map().clone();
+            for class in classes {
+                let merged = class.iter()
+                    .filter_map(|c| map.remove(c))
+                    .fold(None, |acc: Option<f64>, p| Some(acc.unwrap_or(0.0) + p));
+                if let Some(p) = merged {
+                    map.insert(class[0], p);
+                }
+            }
+            Arc::new(Distribution::normalized(map)?)
+        };
+
+        #[cfg(feature = "unicode-normalization")]
+        let distribution = if self.fold_case_and_accents {
+            let mut map = HashMap::new();
+            for (c, &p) in distribution.iter() {
+                let folded = fold(&c.to_string()).chars().collect::<Vec<_>>();
+                for &c in folded.iter() {
+                    *map.entry(c).or_insert(0.0) += p / folded.len() as f64;
+                }
+            }
+            Arc::new(Distribution::normalized(map)?)
+        } else {
+            distribution
+        };
+
+        Ok(distribution)
+    }
+
+    /// the first character of the equivalence class of the character, if any
+    fn representative(&self, c: char) -> Option<char> {
+        self.equivalence_classes.iter()
+            .find(|class| class.contains(&c))
+            .map(|class| class[0])
+    }
+
+    /// set the context of characters for the entropy of the source (default: unigram)
+    ///
+    /// With [`Context::Bigram`](Context::Bigram), I(X,Y) is H(X_n | X_{n-1}) - H_Y(X),
+    /// so that predictable characters carry less information.
+    /// Characters of bigrams absent from the distribution are
+    /// [`TetError::UnknownCharacters`](TetError::UnknownCharacters).
+    pub fn with_context(mut self, context: Context) -> Result<Self> {
+        if let Context::Bigram(bigrams) = &context {
+            let mut unknown = bigrams.chars()
+                .filter(|c| !self.distribution.contains(*c))
+                .collect::<Vec<_>>();
+            if !unknown.is_empty() {
+                unknown.sort_unstable();
+                return Err(TetError::UnknownCharacters(unknown));
+            }
+        }
+
+        self.context = context;
+        Ok(self)
+    }
+
+    /// H(X) of the source in the context (bits/character)
+    fn hx(&self) -> f64 {
+        match &self.context {
+            Context::Unigram => self.distribution.hx(),
+            Context::Bigram(bigrams) => bigrams.hx(),
+        }
+    }
+
+    /// I(X,Y) of an alignment in the context, which is evaluated with H(X) of the character distribution
+    fn ixy_in_context(&self, ixy: f64) -> f64 {
+        match &self.context {
+            Context::Unigram => ixy,
+            Context::Bigram(bigrams) => (ixy - self.distribution.hx() + bigrams.hx()).max(0.0),
+        }
+    }
+
+    /// set which characters are counted as entered in a trial (default: transcribed characters)
+    ///
+    /// See [`TimeBasis`](TimeBasis) for the choices.
+    pub fn time_basis(mut self, time_basis: TimeBasis) -> Self {
+        self.time_basis = time_basis;
+        self
+    }
+
+    /// number of characters entered in a trial, with the length of alignments averaged over evaluations
+    fn entered_chars(&self, presented: &str, transcribed: &str, evaluations: &[OptimalAlignments]) -> f64 {
+        match self.time_basis {
+            TimeBasis::TranscribedChars => transcribed.chars().count() as f64,
+            TimeBasis::AlignedLength => {
+                evaluations.iter()
+                    .map(|alignments| alignments.aligned_length() as f64)
+                    .sum::<f64>()
+                    / evaluations.len() as f64
+            }
+            TimeBasis::PresentedChars => presented.chars().count() as f64,
+        }
+    }
+
+    /// entered characters per second, by [`chars_per_second`](TextEntryThroughput::chars_per_second)
+    /// for the transcribed characters
+    fn entered_chars_per_second(
+        &self,
+        presented: &str,
+        transcribed: &str,
+        evaluations: &[OptimalAlignments],
+        s: std::time::Duration,
+    ) -> Result<f64> {
+        match self.time_basis {
+            TimeBasis::TranscribedChars => Self::chars_per_second(transcribed, s),
+            _ if s.is_zero() => Err(TetError::ZeroDuration),
+            _ => Ok(self.entered_chars(presented, transcribed, evaluations) / s.as_secs_f64()),
+        }
+    }
+
+    /// set handling of control characters absent from the distribution (default: reject)
+    ///
+    /// Affected characters are reported by [`TetReport::sanitized_chars`](TetReport::sanitized_chars).
+    pub fn sanitize(mut self, sanitize: SanitizePolicy) -> Self {
+        self.sanitize = sanitize;
+        self
+    }
+
+    /// whether the character is sanitized
+    fn is_stray(&self, c: char) -> bool {
+        self.sanitize != SanitizePolicy::Reject && c.is_control() && !self.distribution.contains(c)
+    }
+
+    /// distinct characters of texts affected by sanitization, sorted
+    fn sanitized_chars(&self, presented: &str, transcribed: &str) -> Vec<char> {
+        let mut chars = presented.chars()
+            .chain(transcribed.chars())
+            .filter(|&c| self.is_stray(c))
+            .collect::<Vec<_>>();
+        chars.sort_unstable();
+        chars.dedup();
+
+        chars
+    }
+
+    /// normalize a text before alignment
+    ///
+    /// Control characters are sanitized first, then excluded characters are removed,
+    /// then characters are replaced by the first ones of their equivalence classes, then whitespace is collapsed, then case and accents are folded,
+    /// and finally the text is composed into NFC.
+    fn normalize<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        let text = if !text.chars().any(|c| self.is_stray(c)) {
+            Cow::Borrowed(text)
+        } else {
+            Cow::Owned(text.chars()
+                .filter_map(|c| {
+                    match self.sanitize {
+                        SanitizePolicy::Strip if self.is_stray(c) => None,
+                        SanitizePolicy::Replace(replacement) if self.is_stray(c) => Some(replacement),
+                        _ => Some(c),
+                    }
+                })
+                .collect())
+        };
+
+        let text = if self.excluded_chars.is_empty() {
+            text
+        } else {
+            Cow::Owned(text.chars().filter(|c| !self.excluded_chars.contains(c)).collect())
+        };
+
+        let text = if !text.chars().any(|c| self.representative(c).is_some_and(|r| r != c)) {
+            text
+        } else {
+            Cow::Owned(text.chars().map(|c| self.representative(c).unwrap_or(c)).collect())
+        };
+
+        let text = if self.collapse_whitespace {
+            Cow::Owned(text.split_whitespace().collect::<Vec<_>>().join(" "))
+        } else {
+            text
+        };
+
+        #[cfg(feature = "unicode-normalization")]
+        let text = if self.fold_case_and_accents {
+            Cow::Owned(fold(&text))
+        } else {
+            text
+        };
+
+        #[cfg(feature = "unicode-normalization")]
+        if self.nfc {
+            use unicode_normalization::UnicodeNormalization;
+
+            return Cow::Owned(text.nfc().collect());
+        }
+
+        text
     }
 
+    /// English letters and space
+    ///
+    /// See [`Distribution::default`](Distribution::default).
     pub fn alphabet_letter_distribution() -> Self {
-        let alphabets = [
-            'a', 'b', 'c', 'd', 'e',
-            'f', 'g', 'h', 'i', 'j',
-            'k', 'l', 'm', 'n', 'o',
-            'p', 'q', 'r', 's', 't',
-            'u', 'v', 'w', 'x', 'y',
-            'z', ' '
-        ];
+        Self::new(Distribution::default())
+    }
 
-        // ref. http://www.macfreek.nl/memory/Letter_Distribution#Letter_Frequency
-        let distribution = [
-            0.06545420428810268, 0.012614349400134882, 0.022382079660795914, 0.032895839710101495, 0.10287480840814522,
-            0.019870906945619955, 0.01628201251975626, 0.0498866519336527, 0.05679944220647908, 0.0009771967640664421,
-            0.005621008826086285, 0.03324279082953061, 0.020306796250368523, 0.057236004874678816, 0.061720746945911634,
-            0.015073764715016882, 0.0008384527300266635, 0.049980287430261394, 0.05327793252372975, 0.07532249847431097,
-            0.022804128240333354, 0.007977317166161044, 0.017073508770571122, 0.0014120607927983009, 0.014305632773116854,
-            0.0005138874382474097, 0.18325568938199557];
-
-        let map = alphabets.iter().cloned()
-            .zip(distribution.iter().cloned())
-            .collect::<HashMap<_, _>>();
+    /// alphabet letter distribution with newline and tab
+    ///
+    /// `'\n'` and `'\t'` are added with probabilities 0.01 and 0.001 (rough frequencies in prose),
+    /// then all probabilities are renormalized to sum to 1.
+    pub fn alphabet_with_whitespace_distribution() -> Self {
+        let mut map = Self::alphabet_letter_distribution().distribution.map.clone();
+        map.insert('\n', 0.01);
+        map.insert('\t', 0.001);
 
-        let distribution = Distribution::with_map(map);
+        let distribution = Distribution::normalized(map)
+            .expect("probabilities are positive");
 
         Self::new(distribution)
     }
 
+    /// uniform distribution of a–z, A–Z, 0–9 and space (63 characters)
+    ///
+    /// Frequencies are not empirically grounded; every character is equally likely.
+    pub fn alphanumeric_distribution() -> Self {
+        let chars = ('a'..='z')
+            .chain('A'..='Z')
+            .chain('0'..='9')
+            .chain(std::iter::once(' '));
+
+        Self::uniform(chars)
+    }
+
+    /// uniform distribution of printable ASCII characters including space (95 characters)
+    ///
+    /// Frequencies are not empirically grounded; every character is equally likely.
+    pub fn ascii_printable_distribution() -> Self {
+        Self::uniform(' '..='~')
+    }
+
+    fn uniform<I: Iterator<Item=char>>(chars: I) -> Self {
+        let chars = chars.collect::<Vec<_>>();
+        let p = 1.0 / chars.len() as f64;
+
+        let map = chars.into_iter()
+            .map(|c| (c, p))
+            .collect::<HashMap<_, _>>();
+
+        Self::new(Distribution::with_map(map))
+    }
+
+    /// characters per second: the number of transcribed characters over the time
+    ///
+    /// - transcribed: transcribed text
+    /// - s: time in seconds required for entry transcribed text
+    pub fn chars_per_second(transcribed: &str, s: std::time::Duration) -> Result<f64> {
+        if s.is_zero() {
+            return Err(TetError::ZeroDuration);
+        }
+
+        Ok(transcribed.chars().count() as f64 / s.as_secs_f64())
+    }
+
+    /// time of entry of the transcribed text at the words per minute
+    ///
+    /// A word is 5 characters (including spaces), so the time is `(characters / 5) / wpm` minutes,
+    /// e.g. for a dataset recording only words per minute.
+    ///
+    /// Words per minute not positive and finite, or so small that the time overflows
+    /// [`Duration`](std::time::Duration), are [`TetError::InvalidWpm`](TetError::InvalidWpm).
+    ///
+    /// - transcribed: transcribed text
+    /// - wpm: words per minute
+    pub fn seconds_for_wpm(transcribed: &str, wpm: f64) -> Result<std::time::Duration> {
+        if wpm.is_nan() || wpm.is_infinite() || wpm <= 0.0 {
+            return Err(TetError::InvalidWpm(wpm));
+        }
+
+        let minutes = transcribed.chars().count() as f64 / 5.0 / wpm;
+
+        std::time::Duration::try_from_secs_f64(minutes * 60.0)
+            .map_err(|_| TetError::InvalidWpm(wpm))
+    }
+
+    /// theoretical maximum of text entry throughput (bits/s)
+    ///
+    /// H_max (see [`Distribution::entropy_max`](Distribution::entropy_max)) times
+    /// characters per second.
+    pub fn theoretical_ceiling(&self, cps: f64) -> f64 {
+        self.distribution.entropy_max() * cps
+    }
+
+    /// compute a word error rate
+    ///
+    /// Texts are split into words on whitespace,
+    /// and the minimum string distance between the word sequences is divided by
+    /// the number of presented words.
+    /// When no word is presented, the rate is 0 for an empty transcription and 1 otherwise.
+    pub fn wer(presented: &str, transcribed: &str) -> f64 {
+        let presented = presented.split_whitespace().collect::<Vec<_>>();
+        let transcribed = transcribed.split_whitespace().collect::<Vec<_>>();
+
+        if presented.is_empty() {
+            return if transcribed.is_empty() { 0.0 } else { 1.0 };
+        }
+
+        let d = optimal_alignments::msd(&presented, &transcribed);
+
+        d[presented.len()][transcribed.len()] as f64 / presented.len() as f64
+    }
+
+    /// compute the minimum string distance between presented and transcribed texts
+    ///
+    /// The number of insertions, omissions and substitutions (not normalized),
+    /// weighted by [`costs`](TextEntryThroughput::costs).
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    pub fn min_string_distance(&self, presented: &str, transcribed: &str) -> Result<u32> {
+        let (presented, transcribed): (Vec<char>, Vec<char>) = (
+            self.normalize(presented).chars().collect(),
+            self.normalize(transcribed).chars().collect()
+        );
+        optimal_alignments::check_length(&presented, &transcribed, &self.options)?;
+
+        let d = optimal_alignments::msd_with_costs(&presented, &transcribed, Costs::of(&self.options));
+
+        u32::try_from(d[presented.len()][transcribed.len()])
+            .map_err(|_| TetError::InputTooLong { limit: self.options.max_cells })
+    }
+
+    /// compute a character error rate
+    ///
+    /// The minimum string distance is divided by the number of presented characters.
+    /// When no character is presented, the rate is 0 for an empty transcription and 1 otherwise.
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    pub fn cer(&self, presented: &str, transcribed: &str) -> Result<f64> {
+        let distance = self.min_string_distance(presented, transcribed)?;
+
+        Ok(Self::error_rate(distance, self.normalize(presented).chars().count()))
+    }
+
+    fn error_rate(distance: u32, presented: usize) -> f64 {
+        match (distance, presented) {
+            (0, 0) => 0.0,
+            (_, 0) => 1.0,
+            _ => distance as f64 / presented as f64,
+        }
+    }
+
+    /// align presented and transcribed texts
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    pub fn alignments(&self, presented: &str, transcribed: &str) -> Result<OptimalAlignments<'_>> {
+        let (presented, transcribed) = (self.normalize(presented), self.normalize(transcribed));
+
+        OptimalAlignments::with_options(&presented, &transcribed, &self.distribution, &self.options)
+    }
+
+    /// every optimal alignment of presented and transcribed texts, i.e. of the minimum string distance
+    ///
+    /// Unlike averaging over alignments, [`max_alignments`](TextEntryThroughput::max_alignments) is not applied,
+    /// so the number of alignments may grow exponentially with the number of errors.
+    /// Each alignment is returned once, in the order of tracing back.
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    pub fn align_all(&self, presented: &str, transcribed: &str) -> Result<Vec<Alignment>> {
+        let (presented, transcribed): (Vec<char>, Vec<char>) = (
+            self.normalize(presented).chars().collect(),
+            self.normalize(transcribed).chars().collect()
+        );
+        optimal_alignments::check_length(&presented, &transcribed, &self.options)?;
+
+        // distinct moves at each step never trace back the same alignment twice
+        let (alignments, _) = optimal_alignments::all_alignments(
+            &presented, &transcribed, Costs::of(&self.options), &mut TetScratch::new(), None,
+        );
+
+        Ok(alignments)
+    }
+
+    /// evaluate all optimal alignments when averaging, or the selected one otherwise
+    ///
+    /// Texts are expected to be normalized.
+    fn evaluations(&self, presented: &str, transcribed: &str) -> Result<Vec<OptimalAlignments<'_>>> {
+        self.evaluations_with_scratch(presented, transcribed, &mut TetScratch::new())
+            .map(|(evaluations, _)| evaluations)
+    }
+
+    /// [`evaluations`](TextEntryThroughput::evaluations) reusing the matrix of the scratch
+    ///
+    /// Also returns whether averaging was given up for more alignments than `max_alignments`.
+    fn evaluations_with_scratch(
+        &self,
+        presented: &str,
+        transcribed: &str,
+        scratch: &mut TetScratch,
+    ) -> Result<(Vec<OptimalAlignments<'_>>, bool)> {
+        self.check_characters(presented, transcribed)?;
+
+        if self.average_alignments {
+            let (symbols_p, symbols_t): (Vec<char>, Vec<char>) = (
+                presented.chars().collect(),
+                transcribed.chars().collect()
+            );
+            optimal_alignments::check_length(&symbols_p, &symbols_t, &self.options)?;
+
+            let costs = Costs::of(&self.options);
+            let (alignments, truncated) = optimal_alignments::all_alignments(
+                &symbols_p, &symbols_t, costs, scratch, self.max_alignments,
+            );
+            let evaluations = alignments.into_iter()
+                .map(|alignment| {
+                    OptimalAlignments::from_alignment(alignment, &*self.distribution, &self.options)
+                })
+                .collect::<Result<_>>()?;
+
+            return Ok((evaluations, truncated));
+        }
+
+        let evaluation = OptimalAlignments::with_scratch(
+            presented, transcribed, &self.distribution, &self.options, scratch,
+        )?;
+
+        Ok((vec![evaluation], false))
+    }
+
+    /// list all characters of texts absent from the distribution at once
+    fn check_characters(&self, presented: &str, transcribed: &str) -> Result<()> {
+        check_characters(&self.distribution, presented, transcribed)
+    }
+
+    /// I(X,Y): bits/character, averaged over evaluated alignments
+    ///
+    /// With `rayon` feature, I(X,Y) of each alignment is computed in parallel
+    /// but summed in order, so the average is the same as the sequential one.
+    fn ixy(&self, evaluations: &[OptimalAlignments]) -> Option<f64> {
+        self.ixys(evaluations).map(|ixys| Self::mean_ixy(&ixys))
+    }
+
+    /// I(X,Y): bits/character of each evaluated alignment
+    fn ixys(&self, evaluations: &[OptimalAlignments]) -> Option<Vec<f64>> {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+
+            evaluations.par_iter()
+                .map(|alignments| alignments.ixy().map(|ixy| self.ixy_in_context(ixy)))
+                .collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            evaluations.iter()
+                .map(|alignments| alignments.ixy().map(|ixy| self.ixy_in_context(ixy)))
+                .collect()
+        }
+    }
+
+    /// mean of I(X,Y), summed in order
+    fn mean_ixy(ixys: &[f64]) -> f64 {
+        let sum = ixys.iter()
+            .fold(0.0, |acc, ixy| acc + ixy);
+
+        sum / ixys.len() as f64
+    }
+
+    /// compute information transmitted by a trial (bits)
+    ///
+    /// I(X,Y) (bits/character) times the number of entered characters (see [`TimeBasis`](TimeBasis)).
+    /// Sum of bits over total time gives the pooled throughput of trials.
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    pub fn bits_transmitted(&self, presented: &str, transcribed: &str) -> Result<f64> {
+        let (presented, transcribed) = (self.normalize(presented), self.normalize(transcribed));
+
+        let evaluations = self.evaluations(&presented, &transcribed)?;
+        let characters = self.entered_chars(&presented, &transcribed, &evaluations);
+
+        self.ixy(&evaluations)
+            .map(|ixy| ixy * characters)
+            .ok_or(TetError::UndefinedInformation)
+    }
+
+    /// compute H_Y(X): residual entropy of the source given the transcription (bits/character)
+    ///
+    /// H(X) - I(X,Y), with I(X,Y) averaged over evaluated alignments as in
+    /// [`calc`](TextEntryThroughput::calc).
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    pub fn residual_entropy(&self, presented: &str, transcribed: &str) -> Result<f64> {
+        let (presented, transcribed) = (self.normalize(presented), self.normalize(transcribed));
+
+        self.ixy(&self.evaluations(&presented, &transcribed)?)
+            .map(|ixy| self.hx() - ixy)
+            .ok_or(TetError::UndefinedInformation)
+    }
+
     /// compute a text entry throughput (bits/s)
     ///
     /// - presented: presented text
     /// - transcribed: transcribed text
     /// - s: time in seconds required for entry transcribed text
-    pub fn calc(&self, presented: &str, transcribed: &str, s: std::time::Duration) -> Option<f64>    {
-        use optimal_alignments::OptimalAlignments;
+    ///
+    /// Memory needed to align texts grows with `presented.len() * transcribed.len()`,
+    /// so texts exceeding [`max_cells`](TextEntryThroughput::max_cells)
+    /// are rejected with [`TetError::InputTooLong`](TetError::InputTooLong).
+    ///
+    /// A result is finite or an error.
+    /// With [`average_alignments`](TextEntryThroughput::average_alignments) but without
+    /// [`max_alignments`](TextEntryThroughput::max_alignments),
+    /// every optimal alignment is evaluated, whose number can grow exponentially with the length of texts.
+    pub fn calc(&self, presented: &str, transcribed: &str, s: std::time::Duration) -> Result<f64> {
+        self.calc_with_scratch(presented, transcribed, s, &mut TetScratch::new())
+    }
 
-        let characters_per_second = transcribed.chars().count() as f64 / s.as_secs_f64();
+    /// compute a text entry throughput (bits/s) for a time in milliseconds, as logged by most experiment software
+    ///
+    /// Same as [`calc`](TextEntryThroughput::calc) with `Duration::from_millis(millis)`;
+    /// 0 ms is [`TetError::ZeroDuration`](TetError::ZeroDuration).
+    pub fn calc_millis(&self, presented: &str, transcribed: &str, millis: u64) -> Result<f64> {
+        self.calc(presented, transcribed, std::time::Duration::from_millis(millis))
+    }
 
-        let alignments = OptimalAlignments::new(presented, transcribed, &self.distribution);
-        alignments.ixy().map(|ixy| ixy * characters_per_second)
+    /// compute a text entry throughput (bits/s), reusing the matrix of the scratch
+    ///
+    /// The result is the same as [`calc`](TextEntryThroughput::calc),
+    /// which allocates a matrix of `(presented.len() + 1) * (transcribed.len() + 1)` distances every call.
+    /// Here the matrix of the scratch is reallocated only when texts are longer than ever before,
+    /// saving allocations in a loop over many trials.
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    /// - s: time in seconds required for entry transcribed text
+    /// - scratch: buffer shared between calls
+    pub fn calc_with_scratch(
+        &self,
+        presented: &str,
+        transcribed: &str,
+        s: std::time::Duration,
+        scratch: &mut TetScratch,
+    ) -> Result<f64> {
+        self.throughput_and_cps(presented, transcribed, s, scratch)
+            .map(|(throughput, _)| throughput)
     }
-}
 
-#[cfg(test)]
-mod test {
-    use crate::TextEntryThroughput;
+    /// text entry throughput (bits/s) and characters per second
+    fn throughput_and_cps(
+        &self,
+        presented: &str,
+        transcribed: &str,
+        s: std::time::Duration,
+        scratch: &mut TetScratch,
+    ) -> Result<(f64, f64)> {
+        let (presented, transcribed) = (self.normalize(presented), self.normalize(transcribed));
 
-    #[test]
-    fn text_entry_throughput_test() {
-        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "calc",
+            presented = presented.chars().count(),
+            transcribed = transcribed.chars().count(),
+            seconds = s.as_secs_f64(),
+        ).entered();
 
-        let presented = "my watch fell in the waterprevailing wind from the east";
-        let transcribed = "my wacch fell in waterpreviling wind on the east";
-        let s = std::time::Duration::from_secs(12);
+        if s.is_zero() {
+            return Err(TetError::ZeroDuration);
+        }
 
-        let throughput = tet.calc(presented, transcribed, s).unwrap();
+        let (evaluations, _) = self.evaluations_with_scratch(&presented, &transcribed, scratch)?;
+        let characters_per_second = self.entered_chars_per_second(&presented, &transcribed, &evaluations, s)?;
 
-        // 3.238741333352314 * 4.0 = 12.954965333409256
-        // -> significant digits
-        // 3.238 * 4.000 = 12.952 (on the paper)
-        // paper: https://dl.acm.org/doi/fullHtml/10.1145/3290605.3300866
-        assert!((throughput - 12.954965333409255).abs() < 0.0001);
+        // NaN or infinity is not a throughput
+        let throughput = self.ixy(&evaluations)
+            .map(|ixy| ixy * characters_per_second)
+            .filter(|throughput| throughput.is_finite())
+            .ok_or(TetError::UndefinedInformation)?;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(characters_per_second, throughput, "text entry throughput");
+
+        Ok((throughput, characters_per_second))
+    }
+
+    /// compute a text entry throughput normalized by the theoretical ceiling
+    ///
+    /// The throughput is divided by H_max times characters per second
+    /// (see [`theoretical_ceiling`](TextEntryThroughput::theoretical_ceiling)),
+    /// so that throughputs of alphabets of different sizes are compared.
+    /// Unlike normalizing by H(X), an imperfect or non-uniform source stays below 1,
+    /// and the result is in [0, 1] with the unigram [`Context`](Context).
+    /// With [`Context::Bigram`](Context::Bigram), H(X_n | X_{n-1}) of another corpus
+    /// is not bounded by H_max of the distribution, so it may exceed 1.
+    /// Fails with [`TetError::UndefinedInformation`](TetError::UndefinedInformation) if the ceiling is 0.
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    /// - s: time in seconds required for entry transcribed text
+    pub fn normalized_throughput(&self, presented: &str, transcribed: &str, s: std::time::Duration) -> Result<f64> {
+        let (throughput, characters_per_second) = self.throughput_and_cps(presented, transcribed, s, &mut TetScratch::new())?;
+        let ceiling = self.theoretical_ceiling(characters_per_second);
+
+        Some(throughput / ceiling)
+            .filter(|normalized| normalized.is_finite())
+            .ok_or(TetError::UndefinedInformation)
+    }
+
+    /// compute a text entry throughput in the unit of time
+    ///
+    /// [`Rate::PerSecond`](Rate::PerSecond) is the same as [`calc`](TextEntryThroughput::calc).
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    /// - s: time in seconds required for entry transcribed text
+    /// - unit: unit of time of the throughput
+    pub fn calc_in(&self, presented: &str, transcribed: &str, s: std::time::Duration, unit: Rate) -> Result<f64> {
+        self.calc(presented, transcribed, s)
+            .map(|throughput| throughput * unit.seconds())
     }
-}
\ No newline at end of file
+
+    /// compute a text entry throughput (bits/s) and return the alignments used for it
+    ///
+    /// All optimal alignments are returned when averaging over them,
+    /// otherwise the selected one.
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    /// - s: time in seconds required for entry transcribed text
+    pub fn calc_with_alignment(
+        &self,
+        presented: &str,
+        transcribed: &str,
+        s: std::time::Duration,
+    ) -> Result<(f64, Vec<Alignment>)> {
+        let (presented, transcribed) = (self.normalize(presented), self.normalize(transcribed));
+
+        if s.is_zero() {
+            return Err(TetError::ZeroDuration);
+        }
+
+        let evaluations = self.evaluations(&presented, &transcribed)?;
+        let characters_per_second = self.entered_chars_per_second(&presented, &transcribed, &evaluations, s)?;
+
+        // NaN or infinity is not a throughput, as in calc
+        let throughput = self.ixy(&evaluations)
+            .map(|ixy| ixy * characters_per_second)
+            .filter(|throughput| throughput.is_finite())
+            .ok_or(TetError::UndefinedInformation)?;
+
+        let alignments = evaluations.iter()
+            .map(|alignments| alignments.alignment())
+            .collect();
+
+        Ok((throughput, alignments))
+    }
+
+    /// compute a text entry throughput (bits/s) against the closest of reference texts
+    ///
+    /// The reference with the smallest minimum string distance is chosen
+    /// (the first one among ties), and its index is returned with the throughput.
+    ///
+    /// - references: candidates of presented text
+    /// - transcribed: transcribed text
+    /// - s: time in seconds required for entry transcribed text
+    pub fn calc_best_reference(
+        &self,
+        references: &[&str],
+        transcribed: &str,
+        s: std::time::Duration,
+    ) -> Result<(usize, f64)> {
+        let mut best: Option<(usize, u32)> = None;
+        for (i, reference) in references.iter().enumerate() {
+            let distance = self.min_string_distance(reference, transcribed)?;
+            if best.map_or(true, |(_, d)| distance < d) {
+                best = Some((i, distance));
+            }
+        }
+        let (index, _) = best.ok_or(TetError::NoReference)?;
+
+        self.calc(references[index], transcribed, s)
+            .map(|throughput| (index, throughput))
+    }
+
+    /// compute text entry throughputs (bits/s) of trials
+    pub fn calc_trials(&self, trials: &[Trial]) -> Vec<Result<f64>> {
+        trials.iter()
+            .map(|trial| self.calc(&trial.presented, &trial.transcribed, trial.duration))
+            .collect()
+    }
+
+    /// compute text entry throughputs (bits/s) of trials, each paired with its metadata (e.g. an id or a condition)
+    ///
+    /// A matrix of distances is shared between trials as in
+    /// [`calc_with_scratch`](TextEntryThroughput::calc_with_scratch).
+    pub fn calc_many_tagged<M: Clone>(
+        &self,
+        trials: &[(M, &str, &str, std::time::Duration)],
+    ) -> Vec<(M, Result<f64>)> {
+        let mut scratch = TetScratch::new();
+
+        trials.iter()
+            .map(|(tag, presented, transcribed, s)| {
+                (tag.clone(), self.calc_with_scratch(presented, transcribed, *s, &mut scratch))
+            })
+            .collect()
+    }
+
+    /// compute a text entry throughput (bits/s) from an alignment without aligning texts
+    ///
+    /// Useful for an alignment selected or corrected by hand.
+    ///
+    /// - alignment: aligned presented and transcribed texts
+    /// - s: time in seconds required for entry transcribed text
+    pub fn throughput_from_alignment(&self, alignment: &Alignment, s: std::time::Duration) -> Result<f64> {
+        if s.is_zero() {
+            return Err(TetError::ZeroDuration);
+        }
+
+        let null_pair = alignment.presented.iter()
+            .zip(alignment.transcribed.iter())
+            .any(|(p, t)| p.is_null() && t.is_null());
+        if alignment.presented.len() != alignment.transcribed.len() || null_pair {
+            return Err(TetError::InvalidAlignment);
+        }
+
+        let characters = match self.time_basis {
+            TimeBasis::TranscribedChars => alignment.transcribed.iter().filter(|e| !e.is_null()).count(),
+            TimeBasis::AlignedLength => alignment.presented.len(),
+            TimeBasis::PresentedChars => alignment.presented.iter().filter(|e| !e.is_null()).count(),
+        };
+        let characters_per_second = characters as f64 / s.as_secs_f64();
+
+        OptimalAlignments::from_alignment(alignment.clone(), &*self.distribution, &self.options)?
+            .ixy()
+            .map(|ixy| self.ixy_in_context(ixy) * characters_per_second)
+            .filter(|throughput| throughput.is_finite())
+            .ok_or(TetError::UndefinedInformation)
+    }
+
+    /// compute a text entry throughput (bits/s) from rows of an alignment made elsewhere (e.g. by another aligner)
+    ///
+    /// `None` is NULL, i.e. an insertion in the presented row or an omission in the transcribed row.
+    /// Rows must be of the same length and must not align `None` with `None`.
+    ///
+    /// - presented_row: aligned presented text
+    /// - transcribed_row: aligned transcribed text
+    /// - s: time in seconds required for entry transcribed text
+    pub fn throughput_from_aligned(
+        &self,
+        presented_row: &[Option<char>],
+        transcribed_row: &[Option<char>],
+        s: std::time::Duration,
+    ) -> Result<f64> {
+        if presented_row.len() != transcribed_row.len() {
+            return Err(TetError::InvalidAlignment);
+        }
+
+        let row = |row: &[Option<char>]| {
+            row.iter()
+                .map(|c| c.map_or(Element::Null, Element::Character))
+                .collect()
+        };
+        let alignment = Alignment {
+            presented: row(presented_row),
+            transcribed: row(transcribed_row),
+        };
+
+        self.throughput_from_alignment(&alignment, s)
+    }
+
+    /// compute a text entry throughput (bits/s) from error rates without aligning texts
+    ///
+    /// Useful for error rates measured by another tool.
+    /// p(NULL) is estimated from the rates with the configured estimator.
+    ///
+    /// - rates: p(I), p(M), p(S) and p(C)
+    /// - n_chars: number of transcribed characters
+    /// - s: time in seconds required for entry transcribed text
+    pub fn throughput_from_rates(&self, rates: ErrorRates, n_chars: usize, s: std::time::Duration) -> Result<f64> {
+        if s.is_zero() {
+            return Err(TetError::ZeroDuration);
+        }
+        rates.validate()?;
+
+        let characters_per_second = n_chars as f64 / s.as_secs_f64();
+
+        let channel = Channel {
+            distribution: &*self.distribution,
+            p_null: rates.p_null(self.options.null_estimator),
+            rates,
+            min_probability: self.options.min_probability,
+            substitution_model: self.options.substitution_model,
+        };
+
+        channel.hyx()
+            .map(|hyx| (self.hx() - hyx) * characters_per_second)
+            .filter(|throughput| throughput.is_finite())
+            .ok_or(TetError::UndefinedInformation)
+    }
+
+    /// compute all metrics of a trial at once
+    ///
+    /// Alignments are built only once and every metric of [`TetReport`](TetReport) is derived from them,
+    /// so prefer this to calling [`calc`](TextEntryThroughput::calc), [`cer`](TextEntryThroughput::cer),
+    /// [`min_string_distance`](TextEntryThroughput::min_string_distance) and
+    /// [`alignments`](TextEntryThroughput::alignments) one by one.
+    /// Probabilities are averaged over evaluated alignments like I(X,Y).
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    /// - s: time in seconds required for entry transcribed text
+    pub fn analyze(&self, presented: &str, transcribed: &str, s: std::time::Duration) -> Result<TetReport> {
+        let sanitized_chars = self.sanitized_chars(presented, transcribed);
+        let (presented, transcribed) = (self.normalize(presented), self.normalize(transcribed));
+
+        if s.is_zero() {
+            return Err(TetError::ZeroDuration);
+        }
+
+        let (evaluations, truncated_alignments) =
+            self.evaluations_with_scratch(&presented, &transcribed, &mut TetScratch::new())?;
+        let characters_per_second = self.entered_chars_per_second(&presented, &transcribed, &evaluations, s)?;
+        let ixys = self.ixys(&evaluations)
+            .ok_or(TetError::UndefinedInformation)?;
+        let bits_per_character = Self::mean_ixy(&ixys);
+        let (min, max) = ixys.iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &ixy| (min.min(ixy), max.max(ixy)));
+
+        // NaN or infinity is not a throughput, as in calc
+        let throughput = Some(bits_per_character * characters_per_second)
+            .filter(|throughput| throughput.is_finite())
+            .ok_or(TetError::UndefinedInformation)?;
+
+        // every optimal alignment has the same number of errors
+        let alignments = &evaluations[0];
+        let msd = (alignments.n_insertions() + alignments.n_omissions()) as u64 * self.options.gap_cost as u64
+            + alignments.n_substitutions() as u64 * self.options.substitution_cost as u64;
+        let msd = u32::try_from(msd)
+            .map_err(|_| TetError::InputTooLong { limit: self.options.max_cells })?;
+
+        let mean = |f: fn(&OptimalAlignments) -> f64| {
+            evaluations.iter().map(f).sum::<f64>() / evaluations.len() as f64
+        };
+
+        Ok(TetReport {
+            throughput,
+            throughput_min: min * characters_per_second,
+            throughput_max: max * characters_per_second,
+            chars_per_second: characters_per_second,
+            bits_per_character,
+            cer: Self::error_rate(msd, presented.chars().count()),
+            msd,
+            insertion_probability: mean(|alignments| alignments.insertion_probability()),
+            omission_probability: mean(|alignments| alignments.omission_probability()),
+            substitution_probability: mean(|alignments| alignments.substitution_probability()),
+            probability_of_correct_entries: mean(|alignments| alignments.probability_of_correct_entries()),
+            truncated_alignments,
+            sanitized_chars,
+            alignments: evaluations.iter()
+                .map(|alignments| alignments.alignment())
+                .collect(),
+        })
+    }
+
+    /// compute a text entry throughput (bits/s) of a trial entered in segments of time
+    ///
+    /// The sum of segments is the time required for entry transcribed text.
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    /// - segments: intervals of active entry
+    pub fn calc_segmented(
+        &self,
+        presented: &str,
+        transcribed: &str,
+        segments: &[std::time::Duration],
+    ) -> Result<f64> {
+        let s = segments.iter().sum::<std::time::Duration>();
+        if s.is_zero() {
+            return Err(TetError::ZeroDuration);
+        }
+
+        self.calc(presented, transcribed, s)
+    }
+
+    /// compute a text entry throughput (bits/s) of a trial between two instants
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    /// - start: instant when the entry started
+    /// - end: instant when the entry ended
+    pub fn calc_between(
+        &self,
+        presented: &str,
+        transcribed: &str,
+        start: std::time::Instant,
+        end: std::time::Instant,
+    ) -> Result<f64> {
+        let s = end.checked_duration_since(start)
+            .ok_or(TetError::EndBeforeStart)?;
+
+        self.calc(presented, transcribed, s)
+    }
+}
+
+/// lowercase the text, decompose it into NFD and strip combining marks
+#[cfg(feature = "unicode-normalization")]
+fn fold(text: &str) -> String {
+    use unicode_normalization::char::is_combining_mark;
+    use unicode_normalization::UnicodeNormalization;
+
+    text.to_lowercase()
+        .nfd()
+        .filter(|&c| !is_combining_mark(c))
+        .collect()
+}
+
+/// list all characters of texts absent from the distribution at once
+fn check_characters(distribution: &Distribution, presented: &str, transcribed: &str) -> Result<()> {
+    let mut unknown = presented.chars()
+        .chain(transcribed.chars())
+        .filter(|&c| !distribution.contains(c))
+        .collect::<Vec<_>>();
+
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        unknown.sort_unstable();
+        unknown.dedup();
+        Err(TetError::UnknownCharacters(unknown))
+    }
+}
+
+/// compute a text entry throughput (bits/s) under the distribution with default options
+///
+/// The same as [`TextEntryThroughput::calc`](TextEntryThroughput::calc) of a default-configured instance,
+/// but the distribution is borrowed, e.g. to compare candidate distributions in a loop.
+///
+/// - distribution: distribution of characters of the source
+/// - presented: presented text
+/// - transcribed: transcribed text
+/// - s: time in seconds required for entry transcribed text
+pub fn throughput(distribution: &Distribution, presented: &str, transcribed: &str, s: std::time::Duration) -> Result<f64> {
+    let characters_per_second = TextEntryThroughput::chars_per_second(transcribed, s)?;
+    check_characters(distribution, presented, transcribed)?;
+
+    // NaN or infinity is not a throughput, as in calc
+    OptimalAlignments::with_options(presented, transcribed, distribution, &AlignmentOptions::default())?
+        .ixy()
+        .map(|ixy| ixy * characters_per_second)
+        .filter(|throughput| throughput.is_finite())
+        .ok_or(TetError::UndefinedInformation)
+}
+
+impl Default for TextEntryThroughput {
+    /// see [`alphabet_letter_distribution`](TextEntryThroughput::alphabet_letter_distribution)
+    fn default() -> Self {
+        Self::alphabet_letter_distribution()
+    }
+}
+
+impl From<Distribution> for TextEntryThroughput {
+    fn from(distribution: Distribution) -> Self {
+        Self::new(distribution)
+    }
+}
+
+impl TryFrom<HashMap<char, f64>> for TextEntryThroughput {
+    type Error = TetError;
+
+    /// probabilities are normalized to sum to 1
+    fn try_from(map: HashMap<char, f64>) -> Result<Self, Self::Error> {
+        Distribution::normalized(map).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn text_entry_throughput_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let throughput = tet.calc(presented, transcribed, s).unwrap();
+
+        // 3.238741333352314 * 4.0 = 12.954965333409256
+        // -> significant digits
+        // 3.238 * 4.000 = 12.952 (on the paper)
+        // paper: https://dl.acm.org/doi/fullHtml/10.1145/3290605.3300866
+        assert!((throughput - 12.954965333409255).abs() < 0.0001);
+    }
+
+    #[test]
+    fn distribution_iter_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let sum = tet.distribution.iter()
+            .map(|(_, p)| p)
+            .sum::<f64>();
+        assert!((sum - 1.0).abs() < 0.0000001);
+
+        let sum = (&tet.distribution).into_iter()
+            .map(|(_, p)| p)
+            .sum::<f64>();
+        assert!((sum - 1.0).abs() < 0.0000001);
+    }
+
+    #[test]
+    fn distribution_retain_test() {
+        let mut distribution = Distribution::clone(&TextEntryThroughput::alphabet_letter_distribution().distribution);
+        distribution.retain(|c| c != &' ');
+
+        assert!(!distribution.contains(' '));
+        assert_eq!(distribution.iter().count(), 26);
+
+        let sum = distribution.iter()
+            .map(|(_, p)| p)
+            .sum::<f64>();
+        assert!((sum - 1.0).abs() < 0.0000001);
+    }
+
+    #[test]
+    fn entropy_max_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        assert_eq!(tet.distribution.entropy_max(), 27f64.log2());
+        assert_eq!(tet.theoretical_ceiling(4.0), 27f64.log2() * 4.0);
+    }
+
+    #[test]
+    fn whitespace_distribution_test() {
+        let tet = TextEntryThroughput::alphabet_with_whitespace_distribution();
+        assert!((tet.distribution.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 0.00000000001);
+        assert!(tet.distribution.contains('\n'));
+        assert!(tet.distribution.contains('\t'));
+
+        let presented = "my watch fell in the water\nprevailing wind from the east";
+        let transcribed = "my wacch fell in water\npreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let throughput = tet.calc(presented, transcribed, s).unwrap();
+        assert!(throughput.is_finite());
+        assert!(throughput > 0.0);
+    }
+
+    #[test]
+    fn uniform_distribution_test() {
+        let tet = TextEntryThroughput::alphanumeric_distribution();
+        assert_eq!(tet.distribution.iter().count(), 63);
+        assert!(tet.distribution.hx().is_finite());
+        assert!((tet.distribution.hx() - 63f64.log2()).abs() < 0.00000000001);
+
+        let tet = TextEntryThroughput::ascii_printable_distribution();
+        assert_eq!(tet.distribution.iter().count(), 95);
+        assert!(tet.distribution.contains('~'));
+        assert!(tet.distribution.hx().is_finite());
+        assert!((tet.distribution.hx() - 95f64.log2()).abs() < 0.00000000001);
+    }
+
+    #[test]
+    fn add_char_test() {
+        let mut distribution = Distribution::clone(&TextEntryThroughput::alphabet_letter_distribution().distribution);
+        distribution.add_char('\'', 0.01).unwrap();
+
+        assert_eq!(distribution.get('\''), Some(0.01));
+        assert!((distribution.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 0.00000000001);
+    }
+
+    #[test]
+    fn statistics_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let stats = tet.distribution.statistics();
+
+        assert_eq!(stats.size, 27);
+        assert!((stats.entropy - 4.09).abs() < 0.01);
+        assert_eq!(stats.entropy_max, 27f64.log2());
+        assert!((stats.perplexity - stats.entropy.exp2()).abs() < 0.00000000001);
+        assert!((stats.redundancy - (1.0 - stats.entropy / 27f64.log2())).abs() < 0.00000000001);
+        assert_eq!(stats.most_common.map(|(c, _)| c), Some(' '));
+    }
+
+    #[test]
+    fn wer_test() {
+        assert_eq!(TextEntryThroughput::wer("the quick fox", "the quik fox"), 1.0 / 3.0);
+        assert_eq!(TextEntryThroughput::wer("the  quick fox ", " the quick   fox"), 0.0);
+        assert_eq!(TextEntryThroughput::wer("the quick fox", ""), 1.0);
+        assert_eq!(TextEntryThroughput::wer("", ""), 0.0);
+        assert_eq!(TextEntryThroughput::wer(" ", "fox"), 1.0);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tracing_test::traced_test]
+    #[test]
+    fn tracing_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        tet.calc(presented, transcribed, s).unwrap();
+
+        assert!(logs_contain("presented=55"));
+        assert!(logs_contain("transcribed=48"));
+        assert!(logs_contain("msd=9"));
+        assert!(logs_contain("optimal alignments"));
+        assert!(logs_contain("component probabilities"));
+        assert!(logs_contain("mutual information"));
+        assert!(logs_contain("text entry throughput"));
+    }
+
+    #[test]
+    fn bits_transmitted_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let bits = tet.bits_transmitted(presented, transcribed).unwrap();
+        let throughput = tet.calc(presented, transcribed, s).unwrap();
+
+        // 3.238741333352314 * 48
+        assert!((bits - 155.45958400091107).abs() < 0.001);
+        assert!((bits / s.as_secs_f64() - throughput).abs() < 0.00000001);
+    }
+
+    #[test]
+    fn min_string_distance_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        assert_eq!(tet.min_string_distance("quickly", "qucehkly"), Ok(3));
+        assert_eq!(tet.min_string_distance("abcd", "acbd"), Ok(2));
+        assert_eq!(tet.min_string_distance("", ""), Ok(0));
+        assert_eq!(tet.min_string_distance("", "abc"), Ok(3));
+        assert_eq!(tet.min_string_distance("うまぴょい", ""), Ok(5));
+    }
+
+    #[test]
+    fn collapse_whitespace_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let s = std::time::Duration::from_secs(1);
+
+        let perfect = tet.calc("a b", "a b", s).unwrap();
+        assert_ne!(tet.calc("a b", "a  b", s).unwrap(), perfect);
+        assert_eq!(tet.min_string_distance(" a b", "a\t b ").unwrap(), 3);
+
+        let tet = tet.collapse_whitespace(true);
+        assert_eq!(tet.calc("a b", "a  b", s).unwrap(), perfect);
+        assert_eq!(tet.calc(" a\tb\n", "a  b", s).unwrap(), perfect);
+        assert_eq!(tet.min_string_distance(" a b", "a\t b ").unwrap(), 0);
+    }
+
+    #[test]
+    fn costs_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let s = std::time::Duration::from_secs(1);
+        assert_eq!(tet.min_string_distance("abc", "bca"), Ok(2));
+
+        let tet = tet.costs(3, 2);
+        assert_eq!(tet.min_string_distance("abc", "bca"), Ok(4));
+        assert_eq!(tet.analyze("abc", "bca", s).unwrap().msd, 4);
+        assert_eq!(tet.min_string_distance("abc", "abd"), Ok(3));
+        assert_eq!(tet.analyze("abc", "abd", s).unwrap().msd, 3);
+    }
+
+    #[test]
+    fn cer_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        assert_eq!(tet.cer("quickly", "qucehkly"), Ok(3.0 / 7.0));
+        assert_eq!(tet.cer("quickly", ""), Ok(1.0));
+        assert_eq!(tet.cer("", "a"), Ok(1.0));
+        assert_eq!(tet.cer("", ""), Ok(0.0));
+    }
+
+    #[test]
+    fn analyze_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let report = tet.analyze(presented, transcribed, s).unwrap();
+        let alignments = tet.alignments(presented, transcribed).unwrap();
+
+        assert_eq!(report.throughput, tet.calc(presented, transcribed, s).unwrap());
+        assert_eq!(report.bits_per_character, alignments.ixy().unwrap());
+        assert_eq!(report.cer, tet.cer(presented, transcribed).unwrap());
+        assert_eq!(report.msd, tet.min_string_distance(presented, transcribed).unwrap());
+        assert_eq!(report.insertion_probability, alignments.insertion_probability());
+        assert_eq!(report.omission_probability, alignments.omission_probability());
+        assert_eq!(report.substitution_probability, alignments.substitution_probability());
+        assert_eq!(report.probability_of_correct_entries, alignments.probability_of_correct_entries());
+
+        let tet = tet.average_alignments(true);
+        let report = tet.analyze("abcd", "acbd", s).unwrap();
+        assert_eq!(report.throughput, tet.calc("abcd", "acbd", s).unwrap());
+        assert_eq!(report.msd, 2);
+
+        // fails like calc
+        let zero = std::time::Duration::ZERO;
+        assert_eq!(tet.analyze("the fox", "the fix", zero), Err(TetError::ZeroDuration));
+        assert_eq!(tet.analyze("the fox", "", zero), Err(TetError::ZeroDuration));
+    }
+
+    #[test]
+    fn calc_best_reference_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let s = std::time::Duration::from_secs(12);
+
+        let references = [
+            "my watch fell in the water",
+            "prevailing wind from the east",
+        ];
+        let transcribed = "previling wind on the east";
+
+        let (index, throughput) = tet.calc_best_reference(&references, transcribed, s).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(throughput, tet.calc(references[1], transcribed, s).unwrap());
+
+        let (index, _) = tet.calc_best_reference(&["abc", "abd", "abc"], "abx", s).unwrap();
+        assert_eq!(index, 0);
+
+        assert_eq!(tet.calc_best_reference(&[], transcribed, s), Err(TetError::NoReference));
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn nfc_test() {
+        let mut distribution = Distribution::default();
+        distribution.add_char('\u{e9}', 0.01).unwrap();
+        let tet = TextEntryThroughput::new(distribution);
+        let s = std::time::Duration::from_secs(1);
+
+        // precomposed and decomposed é
+        let (nfc, nfd) = ("caf\u{e9}", "cafe\u{301}");
+        assert_eq!(tet.min_string_distance(nfc, nfd), Ok(2));
+
+        let tet = tet.nfc(true);
+        assert_eq!(tet.min_string_distance(nfc, nfd), Ok(0));
+        assert_eq!(tet.calc(nfc, nfd, s), tet.calc(nfc, nfc, s));
+
+        let alignments = tet.alignments(nfc, nfd).unwrap();
+        assert_eq!(alignments.n_correct(), 4);
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn fold_case_and_accents_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let s = std::time::Duration::from_secs(1);
+
+        assert!(matches!(tet.calc("Caf\u{e9}", "cafe", s), Err(TetError::UnknownCharacters(_))));
+
+        let tet = tet.fold_case_and_accents(true).unwrap();
+        assert_eq!(tet.min_string_distance("Caf\u{e9}", "cafe"), Ok(0));
+        assert_eq!(tet.calc("Caf\u{e9}", "cafe", s), tet.calc("cafe", "cafe", s));
+        assert_eq!(tet.calc("CAFE\u{301}", "caf\u{e9}", s), tet.calc("cafe", "cafe", s));
+
+        let alignments = tet.alignments("Caf\u{e9}", "cafe").unwrap();
+        assert_eq!(alignments.n_correct(), 4);
+
+        // probabilities of folded characters are summed
+        let mut distribution = Distribution::default();
+        distribution.add_char('E', 0.01).unwrap();
+        let folded = TextEntryThroughput::new(distribution.clone()).fold_case_and_accents(true).unwrap();
+        assert!(!folded.distribution.contains('E'));
+        let expected = distribution.get('e').unwrap() + distribution.get('E').unwrap();
+        assert!((folded.distribution.get('e').unwrap() - expected).abs() < 0.00000000001);
+
+        // undone by setting false
+        let unfolded = folded.fold_case_and_accents(false).unwrap();
+        assert_eq!(unfolded.distribution.get('E'), distribution.get('E'));
+
+        // nothing is left of combining marks alone
+        let marks = Distribution::with_map([('\u{301}', 1.0)].iter().cloned().collect());
+        assert_eq!(
+            TextEntryThroughput::new(marks).fold_case_and_accents(true).err(),
+            Some(TetError::EmptyDistribution),
+        );
+    }
+
+    #[test]
+    fn equivalence_classes_test() {
+        let distribution = Distribution::from_text("OK 0K LOOK");
+        let tet = TextEntryThroughput::new(distribution.clone());
+        let homoglyphs = tet.clone().equivalence_classes(vec![vec!['O', '0']]).unwrap();
+        let s = std::time::Duration::from_secs(2);
+
+        let report = tet.analyze("LOOK OK", "L0OK OK", s).unwrap();
+        assert_eq!(report.msd, 1);
+
+        // 0 typed for O is correct
+        let report = homoglyphs.analyze("LOOK OK", "L0OK OK", s).unwrap();
+        assert_eq!(report.msd, 0);
+        assert_eq!(report.probability_of_correct_entries, 1.0);
+        assert_eq!(homoglyphs.calc("LOOK OK", "L0OK OK", s), homoglyphs.calc("LOOK OK", "LOOK OK", s));
+
+        // the probability of 0 is merged into O
+        let p = distribution.probability_of('O') + distribution.probability_of('0');
+        assert!((homoglyphs.distribution.probability_of('O') - p).abs() < 0.00000000001);
+        assert!(!homoglyphs.distribution.contains('0'));
+
+        // other classes are merged from the distribution as given
+        let others = homoglyphs.equivalence_classes(vec![vec!['L', 'K']]).unwrap();
+        assert!(others.calc("LOOK OK", "L0OK OK", s).is_ok());
+        assert!((others.distribution.probability_of('0') - distribution.probability_of('0')).abs() < 0.00000000001);
+        assert_eq!(others.distribution.probability_of('K'), 0.0);
+
+        // a class of every character leaves no other
+        let only = tet.equivalence_classes(vec![vec!['L', 'O', '0', 'K', ' ']]).unwrap();
+        assert!((only.distribution.probability_of('L') - 1.0).abs() < 0.00000000001);
+    }
+
+    #[test]
+    fn sanitize_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let s = std::time::Duration::from_secs(2);
+
+        let (presented, transcribed) = ("the fox", "the fox\r");
+        assert_eq!(tet.analyze(presented, transcribed, s), Err(TetError::UnknownCharacters(vec!['\r'])));
+
+        let strip = tet.clone().sanitize(SanitizePolicy::Strip);
+        let report = strip.analyze(presented, transcribed, s).unwrap();
+        assert_eq!(report.sanitized_chars, vec!['\r']);
+        assert_eq!(Ok(report.throughput), strip.calc(presented, "the fox", s));
+        assert_eq!(report.cer, 0.0);
+
+        let replace = tet.sanitize(SanitizePolicy::Replace(' '));
+        let report = replace.analyze("a\0b", "a\0b", s).unwrap();
+        assert_eq!(report.sanitized_chars, vec!['\0']);
+        assert_eq!(Ok(report.throughput), replace.calc("a b", "a b", s));
+
+        // control characters in the distribution are left as they are
+        let whitespace = TextEntryThroughput::alphabet_with_whitespace_distribution().sanitize(SanitizePolicy::Strip);
+        let report = whitespace.analyze("a\nb", "a\nb", s).unwrap();
+        assert!(report.sanitized_chars.is_empty());
+    }
+
+    #[test]
+    fn with_excluded_chars_test() {
+        let mut distribution = Distribution::default();
+        for &c in ['.', ',', '!', '?'].iter() {
+            distribution.add_char(c, 0.01).unwrap();
+        }
+        let tet = TextEntryThroughput::new(distribution);
+        let s = std::time::Duration::from_secs(10);
+
+        let presented = "hello, world! how are you?";
+        let transcribed = "helo world. how ar you";
+
+        let excluded = tet.with_excluded_chars(&['.', ',', '!', '?']);
+        assert!(!excluded.distribution.contains('!'));
+        assert!((excluded.distribution.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 0.00000000001);
+
+        let stripped = TextEntryThroughput::new(Distribution::default());
+        let expected = stripped.calc("hello world how are you", "helo world how ar you", s).unwrap();
+        assert!((excluded.calc(presented, transcribed, s).unwrap() - expected).abs() < 0.00000000001);
+    }
+
+    #[test]
+    fn unknown_characters_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let s = std::time::Duration::from_secs(1);
+
+        assert_eq!(
+            tet.calc("the quick fox", "the quick? f0x?", s),
+            Err(TetError::UnknownCharacters(vec!['0', '?'])),
+        );
+        assert_eq!(
+            tet.bits_transmitted("The fox", "the fox!"),
+            Err(TetError::UnknownCharacters(vec!['!', 'T'])),
+        );
+    }
+
+    #[test]
+    fn input_too_long_test() {
+        let s = std::time::Duration::from_secs(1);
+
+        let tet = TextEntryThroughput::alphabet_letter_distribution().max_cells(20);
+        assert!(tet.calc("abcd", "abcde", s).is_ok());
+        assert_eq!(tet.calc("abcde", "abcde", s), Err(TetError::InputTooLong { limit: 20 }));
+
+        let tet = tet.average_alignments(true);
+        assert_eq!(tet.calc("abcde", "abcde", s), Err(TetError::InputTooLong { limit: 20 }));
+    }
+
+    #[test]
+    fn calc_between_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let start = std::time::Instant::now();
+        let end = start + std::time::Duration::from_secs(12);
+
+        let throughput = tet.calc_between(presented, transcribed, start, end).unwrap();
+        assert!((throughput - 12.954965333409255).abs() < 0.0001);
+
+        assert_eq!(
+            tet.calc_between(presented, transcribed, end, start),
+            Err(TetError::EndBeforeStart)
+        );
+    }
+
+    #[test]
+    fn calc_with_alignment_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let (throughput, alignments) = tet.calc_with_alignment(presented, transcribed, s).unwrap();
+        assert_eq!(throughput, tet.calc(presented, transcribed, s).unwrap());
+        assert_eq!(alignments.len(), 1);
+
+        // 7 omissions and no insertion
+        let alignment = &alignments[0];
+        assert_eq!(alignment.presented.len(), 55);
+        assert_eq!(alignment.presented.iter().filter(|e| e.is_null()).count(), 0);
+        assert_eq!(alignment.transcribed.iter().filter(|e| e.is_null()).count(), 7);
+
+        assert_eq!(
+            tet.calc_with_alignment(presented, transcribed, std::time::Duration::ZERO),
+            Err(TetError::ZeroDuration),
+        );
+    }
+
+    #[test]
+    fn throughput_from_alignment_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let (throughput, alignments) = tet.calc_with_alignment(presented, transcribed, s).unwrap();
+        assert_eq!(tet.throughput_from_alignment(&alignments[0], s), Ok(throughput));
+
+        let mut invalid = alignments[0].clone();
+        invalid.transcribed.pop();
+        assert_eq!(tet.throughput_from_alignment(&invalid, s), Err(TetError::InvalidAlignment));
+
+        let null_pair = Alignment {
+            presented: vec![Element::Character('a'), Element::Null],
+            transcribed: vec![Element::Character('a'), Element::Null],
+        };
+        assert_eq!(tet.throughput_from_alignment(&null_pair, s), Err(TetError::InvalidAlignment));
+
+        assert_eq!(
+            tet.throughput_from_alignment(&alignments[0], std::time::Duration::ZERO),
+            Err(TetError::ZeroDuration),
+        );
+    }
+
+    #[test]
+    fn throughput_test() {
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let english = Distribution::default();
+        let with_whitespace = TextEntryThroughput::alphabet_with_whitespace_distribution().distribution;
+
+        let under_english = throughput(&english, presented, transcribed, s).unwrap();
+        let under_whitespace = throughput(&with_whitespace, presented, transcribed, s).unwrap();
+        assert!((under_english - 12.954965333409255).abs() < 0.0001);
+        assert!((under_english - under_whitespace).abs() > 0.1);
+
+        assert!(matches!(throughput(&english, "abc", "ab1", s), Err(TetError::UnknownCharacters(_))));
+        assert_eq!(throughput(&english, presented, transcribed, std::time::Duration::ZERO), Err(TetError::ZeroDuration));
+    }
+
+    #[test]
+    fn max_alignments_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution().average_alignments(true);
+        // any 5 of 10 characters can be omitted: 252 alignments
+        let (presented, transcribed) = ("aaaaaaaaaa", "aaaaa");
+        let s = std::time::Duration::from_secs(3);
+
+        let report = tet.analyze(presented, transcribed, s).unwrap();
+        assert!(!report.truncated_alignments);
+
+        let report = tet.clone().max_alignments(10).analyze(presented, transcribed, s).unwrap();
+        assert!(report.truncated_alignments);
+        assert!(report.throughput.is_finite());
+
+        let single = tet.average_alignments(false).selection(Selection::PreferDiagonal)
+            .analyze(presented, transcribed, s).unwrap();
+        assert!(!single.truncated_alignments);
+        assert_eq!(report.throughput, single.throughput);
+
+        // billions of alignments are not enumerated beyond the limit
+        let (presented, transcribed) = ("a".repeat(34), "a".repeat(17));
+        let report = TextEntryThroughput::alphabet_letter_distribution()
+            .average_alignments(true)
+            .max_alignments(10)
+            .analyze(&presented, &transcribed, s)
+            .unwrap();
+        assert!(report.truncated_alignments);
+        assert!(report.throughput.is_finite());
+    }
+
+    #[test]
+    fn align_all_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let alignments = tet.align_all("abcd", "acbd").unwrap();
+        assert_eq!(alignments.len(), 3);
+        for alignment in alignments.iter() {
+            assert_eq!(alignment.presented.len(), alignment.transcribed.len());
+            let distance = alignment.operations().iter()
+                .filter(|operation| !matches!(operation, Operation::Match(_)))
+                .count();
+            assert_eq!(distance as u32, tet.min_string_distance("abcd", "acbd").unwrap());
+        }
+        for (i, alignment) in alignments.iter().enumerate() {
+            assert!(!alignments[i + 1..].contains(alignment));
+        }
+
+        assert_eq!(tet.align_all("the fox", "the fox").unwrap().len(), 1);
+        assert_eq!(
+            tet.max_cells(10).align_all("the quick fox", "the quick fox"),
+            Err(TetError::InputTooLong { limit: 10 }),
+        );
+    }
+
+    #[test]
+    fn throughput_from_aligned_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let (throughput, alignments) = tet.calc_with_alignment(presented, transcribed, s).unwrap();
+        let row = |row: &[Element]| {
+            row.iter()
+                .map(|e| match e {
+                    Element::Character(c) => Some(*c),
+                    Element::Null => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let (presented_row, transcribed_row) = (row(&alignments[0].presented), row(&alignments[0].transcribed));
+        assert_eq!(tet.throughput_from_aligned(&presented_row, &transcribed_row, s), Ok(throughput));
+
+        assert_eq!(
+            tet.throughput_from_aligned(&presented_row, &transcribed_row[1..], s),
+            Err(TetError::InvalidAlignment),
+        );
+        assert_eq!(
+            tet.throughput_from_aligned(&[Some('a'), None], &[Some('a'), None], s),
+            Err(TetError::InvalidAlignment),
+        );
+        assert_eq!(
+            tet.throughput_from_aligned(&presented_row, &transcribed_row, std::time::Duration::ZERO),
+            Err(TetError::ZeroDuration),
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_input_test(
+            presented in arbitrary_text(),
+            transcribed in arbitrary_text(),
+            s in prop_oneof![
+                Just(std::time::Duration::ZERO),
+                Just(std::time::Duration::MAX),
+                any::<u64>().prop_map(std::time::Duration::from_nanos),
+                (0u64..100_000).prop_map(std::time::Duration::from_millis),
+            ],
+        ) {
+            let tets = [
+                TextEntryThroughput::alphabet_letter_distribution(),
+                TextEntryThroughput::alphabet_letter_distribution().average_alignments(true).max_alignments(10),
+                TextEntryThroughput::new(Distribution::from_text("a")),
+                TextEntryThroughput::alphabet_letter_distribution().sanitize(SanitizePolicy::Strip),
+            ];
+
+            for tet in tets.iter() {
+                if let Ok(throughput) = tet.calc(&presented, &transcribed, s) {
+                    prop_assert!(throughput.is_finite(), "{}", throughput);
+                }
+            }
+        }
+    }
+
+    /// texts of mostly characters of the distribution, so that many inputs are computed
+    fn arbitrary_text() -> impl Strategy<Value=String> {
+        let common = prop::sample::select(vec!['a', 'e', 'z', ' ']);
+        let any = prop::sample::select(vec![
+            'a', 'e', 'z', ' ', 'A', '0', '\0', '\r', '\n', '\u{301}', 'é', 'う', '🦀',
+        ]);
+
+        prop_oneof![
+            prop::collection::vec(common, 0..24),
+            prop::collection::vec(any, 0..24),
+        ].prop_map(|chars| chars.into_iter().collect())
+    }
+
+    #[test]
+    fn many_alignments_test() {
+        // the last of billions of optimal alignments is found without enumerating them
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let throughput = tet.calc(&"a".repeat(40), &"a".repeat(20), std::time::Duration::from_secs(1)).unwrap();
+        assert!(throughput.is_finite());
+    }
+
+    #[test]
+    fn normalized_throughput_test() {
+        let s = std::time::Duration::from_secs(3);
+
+        // a perfect transcription of a uniform source reaches the ceiling
+        let uniform = TextEntryThroughput::alphanumeric_distribution();
+        let normalized = uniform.normalized_throughput("The Quick Brown Fox 123", "The Quick Brown Fox 123", s).unwrap();
+        assert!((normalized - 1.0).abs() < 0.00000000001);
+
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+        let normalized = tet.normalized_throughput(presented, transcribed, s).unwrap();
+        let ceiling = tet.theoretical_ceiling(TextEntryThroughput::chars_per_second(transcribed, s).unwrap());
+        assert!((normalized - tet.calc(presented, transcribed, s).unwrap() / ceiling).abs() < 0.00000000001);
+        assert!(0.0 < normalized && normalized < 1.0);
+
+        assert_eq!(tet.normalized_throughput("the fox", "", s), Err(TetError::UndefinedInformation));
+        assert_eq!(tet.normalized_throughput("the fox", "the fox", std::time::Duration::ZERO), Err(TetError::ZeroDuration));
+    }
+
+    #[test]
+    fn context_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let source = "the quick brown fox jumps over the lazy dog \
+            my watch fell in the water \
+            prevailing wind from the east \
+            never too rich and never too thin \
+            time to go shopping";
+        let bigram = tet.clone().with_context(Context::Bigram(BigramDistribution::from_text(source))).unwrap();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let unigram_throughput = tet.calc(presented, transcribed, s).unwrap();
+        let bigram_throughput = bigram.calc(presented, transcribed, s).unwrap();
+        assert!(bigram_throughput < unigram_throughput);
+
+        // the error model is not changed
+        let residual = tet.residual_entropy(presented, transcribed).unwrap();
+        assert!((bigram.residual_entropy(presented, transcribed).unwrap() - residual).abs() < 0.00000000001);
+        let (_, alignments) = bigram.calc_with_alignment(presented, transcribed, s).unwrap();
+        let from_alignment = bigram.throughput_from_alignment(&alignments[0], s).unwrap();
+        assert!((from_alignment - bigram_throughput).abs() < 0.00000000001);
+        assert_eq!(tet.clone().with_context(Context::Unigram).unwrap().calc(presented, transcribed, s), Ok(unigram_throughput));
+
+        // a predictable corpus leaves no information, not negative one
+        let predictable = tet.clone().with_context(Context::Bigram(BigramDistribution::from_text("abababab"))).unwrap();
+        assert_eq!(predictable.calc("abab", "abba", s), Ok(0.0));
+
+        // bigrams of characters out of the distribution
+        assert_eq!(
+            tet.with_context(Context::Bigram(BigramDistribution::from_text("ab1a2"))).err(),
+            Some(TetError::UnknownCharacters(vec!['1', '2'])),
+        );
+    }
+
+    #[test]
+    fn time_basis_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        // 55 presented and 48 transcribed characters, without insertions
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let default = tet.calc(presented, transcribed, s).unwrap();
+        let transcribed_chars = tet.clone().time_basis(TimeBasis::TranscribedChars);
+        assert_eq!(transcribed_chars.calc(presented, transcribed, s), Ok(default));
+
+        let presented_chars = tet.clone().time_basis(TimeBasis::PresentedChars);
+        let throughput = presented_chars.calc(presented, transcribed, s).unwrap();
+        assert_ne!(throughput, default);
+        assert!((throughput - default * 55.0 / 48.0).abs() < 0.00000000001);
+        let (_, alignments) = presented_chars.calc_with_alignment(presented, transcribed, s).unwrap();
+        assert_eq!(presented_chars.throughput_from_alignment(&alignments[0], s), Ok(throughput));
+
+        // without insertions, the aligned length is the number of presented characters
+        let report = tet.time_basis(TimeBasis::AlignedLength).analyze(presented, transcribed, s).unwrap();
+        assert!((report.throughput - throughput).abs() < 0.00000000001);
+        assert!((report.chars_per_second - 55.0 / 12.0).abs() < 0.00000000001);
+    }
+
+    #[test]
+    fn chars_per_second_test() {
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        assert_eq!(TextEntryThroughput::chars_per_second(transcribed, s), Ok(4.0));
+        assert_eq!(
+            TextEntryThroughput::chars_per_second(transcribed, std::time::Duration::ZERO),
+            Err(TetError::ZeroDuration),
+        );
+
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let report = tet.analyze(presented, transcribed, s).unwrap();
+        assert_eq!(report.chars_per_second, 4.0);
+        assert_eq!(tet.analyze(presented, transcribed, std::time::Duration::ZERO), Err(TetError::ZeroDuration));
+
+        let report = tet.time_basis(TimeBasis::PresentedChars).analyze(presented, transcribed, s).unwrap();
+        assert_eq!(report.chars_per_second, 55.0 / 12.0);
+    }
+
+    #[test]
+    fn seconds_for_wpm_test() {
+        let transcribed = "the quick brown fox jumps over the lazy dog and runs away ok";
+        assert_eq!(transcribed.chars().count(), 60);
+
+        let s = TextEntryThroughput::seconds_for_wpm(transcribed, 12.0).unwrap();
+        assert_eq!(s, std::time::Duration::from_secs(60));
+        // 12 words of 5 characters per minute
+        assert_eq!(TextEntryThroughput::chars_per_second(transcribed, s), Ok(1.0));
+
+        assert_eq!(TextEntryThroughput::seconds_for_wpm(transcribed, 0.0), Err(TetError::InvalidWpm(0.0)));
+        assert_eq!(TextEntryThroughput::seconds_for_wpm(transcribed, -1.0), Err(TetError::InvalidWpm(-1.0)));
+        assert!(TextEntryThroughput::seconds_for_wpm(transcribed, f64::NAN).is_err());
+        assert_eq!(
+            TextEntryThroughput::seconds_for_wpm(transcribed, f64::INFINITY),
+            Err(TetError::InvalidWpm(f64::INFINITY)),
+        );
+
+        // the time overflows
+        assert_eq!(TextEntryThroughput::seconds_for_wpm("hello", 1e-20), Err(TetError::InvalidWpm(1e-20)));
+    }
+
+    #[test]
+    fn residual_entropy_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let residual = tet.residual_entropy(presented, transcribed).unwrap();
+        let report = tet.analyze(presented, transcribed, s).unwrap();
+        assert!((residual - (tet.distribution.hx() - report.bits_per_character)).abs() < 0.00000000001);
+        assert!((residual - 0.8515677144377292).abs() < 0.00000000001);
+
+        assert_eq!(tet.residual_entropy(presented, presented), Ok(0.0));
+    }
+
+    #[test]
+    fn throughput_range_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution().average_alignments(true);
+        let s = std::time::Duration::from_secs(2);
+
+        // 3 optimal alignments
+        let report = tet.analyze("abcd", "acbd", s).unwrap();
+        assert_eq!(report.alignments.len(), 3);
+        assert!(report.throughput_min <= report.throughput);
+        assert!(report.throughput <= report.throughput_max);
+        assert!(report.throughput_min < report.throughput_max);
+
+        let report = tet.analyze("abcd", "abed", s).unwrap();
+        assert_eq!(report.alignments.len(), 1);
+        assert_eq!(report.throughput_min, report.throughput);
+        assert_eq!(report.throughput_max, report.throughput);
+    }
+
+    #[test]
+    fn calc_with_scratch_test() {
+        let trials = [
+            ("the quick brown fox", "the quik brwn fix", 4),
+            ("my watch fell in the waterprevailing wind from the east", "my wacch fell in waterpreviling wind on the east", 12),
+            ("abcd", "acbd", 2),
+            ("time to go shopping", "tme to go shoppimg", 5),
+            ("the fox", "the fox", 1),
+        ];
+
+        for tet in [
+            TextEntryThroughput::alphabet_letter_distribution(),
+            TextEntryThroughput::alphabet_letter_distribution().average_alignments(true),
+        ].iter() {
+            let mut scratch = TetScratch::new();
+            for &(presented, transcribed, s) in trials.iter() {
+                let s = std::time::Duration::from_secs(s);
+                assert_eq!(
+                    tet.calc_with_scratch(presented, transcribed, s, &mut scratch),
+                    tet.calc(presented, transcribed, s),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn calc_many_tagged_test() {
+        use std::time::Duration;
+
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let trials = [
+            ("p1-qwerty", "the quick brown fox", "the quik brwn fix", Duration::from_secs(4)),
+            ("p1-dvorak", "time to go shopping", "tme to go shoppimg", Duration::from_secs(5)),
+            ("p2-qwerty", "the fox", "the f0x", Duration::from_secs(1)),
+        ];
+
+        let results = tet.calc_many_tagged(&trials);
+        assert_eq!(results.len(), 3);
+        for ((tag, result), (expected, presented, transcribed, s)) in results.iter().zip(trials.iter()) {
+            assert_eq!(tag, expected);
+            assert_eq!(*result, tet.calc(presented, transcribed, *s));
+        }
+        assert!(results[0].1.is_ok());
+        assert_eq!(results[2], ("p2-qwerty", Err(TetError::UnknownCharacters(vec!['0']))));
+    }
+
+    #[test]
+    fn throughput_from_rates_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let rates = tet.alignments(presented, transcribed).unwrap().error_rates();
+        let throughput = tet.throughput_from_rates(rates, transcribed.chars().count(), s).unwrap();
+        assert!((throughput - tet.calc(presented, transcribed, s).unwrap()).abs() < 0.00000000001);
+
+        let out_of_range = ErrorRates { insertion: -0.1, omission: 0.1, ..rates };
+        assert_eq!(tet.throughput_from_rates(out_of_range, 48, s), Err(TetError::InvalidErrorRates));
+        let inconsistent = ErrorRates { correct: 0.5, ..rates };
+        assert_eq!(tet.throughput_from_rates(inconsistent, 48, s), Err(TetError::InvalidErrorRates));
+        assert_eq!(tet.throughput_from_rates(rates, 48, std::time::Duration::ZERO), Err(TetError::ZeroDuration));
+        assert_eq!(tet.throughput_from_rates(rates, 0, std::time::Duration::ZERO), Err(TetError::ZeroDuration));
+    }
+
+    #[test]
+    fn average_alignments_test() {
+        let distribution = TextEntryThroughput::alphabet_letter_distribution().distribution;
+        let tet = TextEntryThroughput::from_shared(distribution).average_alignments(true);
+        let s = std::time::Duration::from_secs(2);
+
+        let (throughput, alignments) = tet.calc_with_alignment("abcd", "acbd", s).unwrap();
+        assert_eq!(alignments.len(), 3);
+
+        let throughputs = alignments.into_iter()
+            .map(|alignment| {
+                OptimalAlignments::from_alignment(alignment, &*tet.distribution, &tet.options).unwrap()
+                    .ixy().unwrap() * 2.0
+            })
+            .collect::<Vec<_>>();
+        let mean = throughputs.iter().sum::<f64>() / 3.0;
+        assert!((throughput - mean).abs() < 0.00000000001);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_average_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution().average_alignments(true);
+
+        let evaluations = tet.evaluations("the quick brown fox", "teh qiuck borwn fxo").unwrap();
+        assert!(evaluations.len() > 1);
+
+        let sequential = evaluations.iter()
+            .map(|alignments| alignments.ixy().unwrap())
+            .fold(0.0, |acc, ixy| acc + ixy)
+            / evaluations.len() as f64;
+        assert_eq!(tet.ixy(&evaluations), Some(sequential));
+    }
+
+    #[test]
+    fn default_test() {
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let throughput = TextEntryThroughput::default().calc(presented, transcribed, s).unwrap();
+        assert!((throughput - 12.954965333409255).abs() < 0.0001);
+
+        assert_eq!(Frequencies::default(), Frequencies::new());
+        assert_eq!(Distribution::default().iter().count(), 27);
+    }
+
+    #[test]
+    fn clone_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution()
+            .selection(Selection::PreferDiagonal);
+        let cloned = tet.clone();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        assert_eq!(cloned.calc(presented, transcribed, s), tet.calc(presented, transcribed, s));
+    }
+
+    #[test]
+    fn from_shared_test() {
+        let distribution = Arc::new(Distribution::clone(
+            &TextEntryThroughput::alphabet_letter_distribution().distribution
+        ));
+        let s = std::time::Duration::from_secs(12);
+        let expected = TextEntryThroughput::from_shared(distribution.clone())
+            .calc("the quick brown fox", "the quik brwn fix", s)
+            .unwrap();
+
+        let handles = (0..4)
+            .map(|_| {
+                let tet = TextEntryThroughput::from_shared(distribution.clone());
+                std::thread::spawn(move || {
+                    tet.calc("the quick brown fox", "the quik brwn fix", s).unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn perfect_transcription_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let s = std::time::Duration::from_secs(4);
+
+        let throughput = tet.calc("the quick ", "the quick ", s).unwrap();
+        assert_eq!(throughput, tet.distribution.hx() * (10.0 / 4.0));
+
+        let averaged = tet.average_alignments(true).calc("the quick ", "the quick ", s).unwrap();
+        assert_eq!(averaged, throughput);
+    }
+
+    #[test]
+    fn calc_in_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let per_second = tet.calc_in(presented, transcribed, s, Rate::PerSecond).unwrap();
+        let per_minute = tet.calc_in(presented, transcribed, s, Rate::PerMinute).unwrap();
+
+        assert_eq!(per_second, tet.calc(presented, transcribed, s).unwrap());
+        assert_eq!(per_minute, per_second * 60.0);
+    }
+
+    #[test]
+    fn calc_segmented_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let segments = [std::time::Duration::from_millis(4500), std::time::Duration::from_millis(7500)];
+
+        let throughput = tet.calc_segmented(presented, transcribed, &segments).unwrap();
+        assert!((throughput - 12.954965333409255).abs() < 0.0001);
+
+        assert_eq!(tet.calc_segmented(presented, transcribed, &[]), Err(TetError::ZeroDuration));
+        assert_eq!(
+            tet.calc_segmented(presented, transcribed, &[std::time::Duration::ZERO; 2]),
+            Err(TetError::ZeroDuration),
+        );
+    }
+
+    #[test]
+    fn calc_millis_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+
+        let throughput = tet.calc_millis(presented, transcribed, 12000).unwrap();
+        assert!((throughput - 12.954965333409255).abs() < 0.0001);
+        assert_eq!(tet.calc_millis(presented, transcribed, 0), Err(TetError::ZeroDuration));
+    }
+
+    #[test]
+    fn conversion_test() {
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let map = TextEntryThroughput::alphabet_letter_distribution().distribution.map.clone();
+
+        let tet = TextEntryThroughput::from(Distribution::with_map(map.clone()));
+        assert!((tet.calc(presented, transcribed, s).unwrap() - 12.954965333409255).abs() < 0.0001);
+
+        let tet = TextEntryThroughput::try_from(map).unwrap();
+        assert!((tet.calc(presented, transcribed, s).unwrap() - 12.954965333409255).abs() < 0.0001);
+
+        let map = [('a', 1.0), ('b', -1.0)].iter().cloned().collect::<HashMap<_, _>>();
+        assert_eq!(TextEntryThroughput::try_from(map).err(), Some(TetError::NegativeProbability('b')));
+
+        let map = [('a', 0.0)].iter().cloned().collect::<HashMap<_, _>>();
+        assert_eq!(TextEntryThroughput::try_from(map).err(), Some(TetError::EmptyDistribution));
+    }
+}
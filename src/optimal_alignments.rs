@@ -1,168 +1,1008 @@
-use crate::distribution::Distribution;
-
-#[derive(Debug, Clone, Eq, PartialEq)]
-enum Element {
-    Character(char),
+use crate::cost::Cost;
+use crate::distribution::{Distribution, Source};
+use crate::error::{Result, TetError};
+use crate::statistics::approx_eq;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::Add;
+
+/// element of an aligned text
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Element<S = char> {
+    Character(S),
+    /// gap for an inserted or omitted character
     Null,
 }
 
-impl Element {
+impl<S> Element<S> {
     pub fn is_null(&self) -> bool {
-        self == &Element::Null
+        matches!(self, Element::Null)
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub struct OptimalAlignments<'a> {
-    distribution: &'a Distribution,
-    presented: Vec<Element>,
-    transcribed: Vec<Element>,
-    p_null: f64,
-    len: usize,
+/// a character as it is, and NULL as `null`
+#[cfg(feature = "serde1")]
+impl<S: Serialize> Serialize for Element<S> {
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        match self {
+            Element::Character(c) => serializer.serialize_some(c),
+            Element::Null => serializer.serialize_none(),
+        }
+    }
 }
 
-impl<'a> OptimalAlignments<'a> {
-    pub fn new(presented: &str, transcribed: &str, distribution: &'a Distribution) -> Self {
-        let (presented, transcribed) = (presented.into(), transcribed.into());
+#[cfg(feature = "serde1")]
+impl<'de, S: Deserialize<'de>> Deserialize<'de> for Element<S> {
+    fn deserialize<Z: Deserializer<'de>>(deserializer: Z) -> Result<Self, Z::Error> {
+        Option::<S>::deserialize(deserializer)
+            .map(|c| c.map_or(Element::Null, Element::Character))
+    }
+}
 
-        let mut slf = Self {
-            distribution,
-            presented: Vec::new(),
-            transcribed: Vec::new(),
-            p_null: 0.0,
-            len: 0,
-        };
+/// presented and transcribed texts aligned position by position
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Alignment<S = char> {
+    /// presented text, with nulls at insertions
+    pub presented: Vec<Element<S>>,
+    /// transcribed text, with nulls at omissions
+    pub transcribed: Vec<Element<S>>,
+}
 
-        let mut d = Self::msd(presented, transcribed);
+/// edit operation at an aligned position
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Operation<S = char> {
+    /// c -> c: correctly entered
+    Match(S),
+    /// c -> c': substituted
+    Substitution { expected: S, got: S },
+    /// NULL -> c: inserted
+    Insertion(S),
+    /// c -> NULL: omitted
+    Omission(S),
+}
 
-        let (presented, transcribed): (Vec<char>, Vec<char>) = (
-            presented.to_string().chars().collect(),
-            transcribed.to_string().chars().collect()
-        );
+impl<S: Clone + PartialEq> Alignment<S> {
+    /// edit operations position by position
+    ///
+    /// Positions aligning NULL with NULL are skipped.
+    pub fn operations(&self) -> Vec<Operation<S>> {
+        self.presented.iter()
+            .zip(self.transcribed.iter())
+            .filter_map(|pair| {
+                match pair {
+                    (Element::Null, Element::Character(t)) => Some(Operation::Insertion(t.clone())),
+                    (Element::Character(p), Element::Null) => Some(Operation::Omission(p.clone())),
+                    (Element::Character(p), Element::Character(t)) => {
+                        if p != t {
+                            Some(Operation::Substitution { expected: p.clone(), got: t.clone() })
+                        } else {
+                            Some(Operation::Match(p.clone()))
+                        }
+                    }
+                    (Element::Null, Element::Null) => None,
+                }
+            })
+            .collect()
+    }
+}
+
+impl<S: Clone + PartialEq + std::fmt::Display> Alignment<S> {
+    /// render the alignment left to right as a diff
+    ///
+    /// Matches are written as they are, substitutions as `[expected/got]`,
+    /// omissions as `{-expected}` and insertions as `{+got}`.
+    /// Literal `[`, `]`, `{`, `}`, `/` and `\` in elements are escaped with a preceding `\`.
+    pub fn to_diff_string(&self) -> String {
+        self.operations()
+            .into_iter()
+            .map(|operation| {
+                match operation {
+                    Operation::Match(c) => escape_diff(&c),
+                    Operation::Substitution { expected, got } => format!("[{}/{}]", escape_diff(&expected), escape_diff(&got)),
+                    Operation::Omission(c) => format!("{{-{}}}", escape_diff(&c)),
+                    Operation::Insertion(c) => format!("{{+{}}}", escape_diff(&c)),
+                }
+            })
+            .collect()
+    }
+}
 
-        let (x, y) = (presented.len(), transcribed.len());
+fn escape_diff<S: std::fmt::Display>(element: &S) -> String {
+    element.to_string()
+        .chars()
+        .fold(String::new(), |mut escaped, c| {
+            if matches!(c, '[' | ']' | '{' | '}' | '/' | '\\') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+            escaped
+        })
+}
 
-        slf.alignments(
-            &presented,
-            &transcribed,
-            &mut d, x, y,
-            Vec::new(),
-            Vec::new(),
-        );
+/// estimator of p(NULL), the probability of the null character in the source
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NullEstimator {
+    /// fraction of aligned positions whose presented element is null (default)
+    ///
+    /// This is the estimator of the paper: p(NULL) = p'(NULL) = N(insertions) / N(aligned positions).
+    #[default]
+    PresentedFraction,
+    /// fraction of null elements over all positions of both aligned strings
+    ///
+    /// Omissions are counted as well as insertions.
+    AllPositions,
+    /// fixed probability, regardless of the alignment
+    Fixed(f64),
+}
 
-        if slf.presented.len() != slf.transcribed.len() {
-            panic!("Something went wrong :sob:");
+/// selection of an alignment among optimal alignments
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Selection {
+    /// the last alignment found by exploring all optimal alignments (default)
+    #[default]
+    Last,
+    /// the alignment found by preferring diagonal moves (match or substitution)
+    ///
+    /// The alignment is traced back from the end of texts,
+    /// taking a diagonal move whenever it is optimal, then an omission, then an insertion.
+    /// This is canonical and minimizes spurious insertions and omissions.
+    PreferDiagonal,
+}
+
+/// basis of the denominators of reported component probabilities
+///
+/// Let N be the length of the alignment, N_p the number of presented characters
+/// and N_t the number of transcribed characters.
+///
+/// Only reported probabilities (e.g. [`OptimalAlignments::error_rates`](OptimalAlignments::error_rates))
+/// are normalized so. H_Y(X) is always computed from probabilities of the paper,
+/// which sum up to 1 as a distribution of the channel must.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateNormalization {
+    /// the paper's normalization (default)
+    ///
+    /// p(I) = N(I) / N, and p(M), p(S) and p(C) are N(x) / N_p scaled by 1 - p(I),
+    /// so that they sum up to 1.
+    #[default]
+    AlignedLength,
+    /// every probability is N(x) / N_p
+    ///
+    /// Insertions are counted against the presented length like error rates of presented characters,
+    /// so probabilities sum up to more than 1 with insertions.
+    PresentedLength,
+    /// every probability is N(x) / N_t
+    ///
+    /// Omissions are counted against the transcribed length like error rates of keystrokes,
+    /// so probabilities sum up to more than 1 with omissions.
+    TranscribedLength,
+}
+
+/// distribution of the probability of a substitution over substituted characters, p_i(j) for j != i
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SubstitutionModel {
+    /// the paper's model (default)
+    ///
+    /// p(S) is divided equally among the other n - 1 characters of the distribution,
+    /// including characters of probability 0, which are never entered.
+    #[default]
+    Uniform,
+    /// p(S) is divided among the other characters in proportion to their probabilities,
+    /// p(S) p(j) / (1 - p(i))
+    ///
+    /// Characters of probability 0 (e.g. by [`Frequencies::entry_char`](crate::Frequencies::entry_char))
+    /// take no substitutions, and common characters are more likely substitutes.
+    Proportional,
+}
+
+/// options of building alignments
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlignmentOptions {
+    /// estimator of p(NULL)
+    pub null_estimator: NullEstimator,
+    /// selection of an alignment among optimal alignments
+    pub selection: Selection,
+    /// floor of probabilities taken logarithms of
+    /// (see [`OptimalAlignments::min_probability`](OptimalAlignments::min_probability))
+    pub min_probability: f64,
+    /// limit of `presented.len() * transcribed.len()` (default: 10,000,000)
+    ///
+    /// Aligning needs a matrix of minimum string distances,
+    /// whose memory grows quadratically with the length of texts.
+    pub max_cells: usize,
+    /// cost of a substitution in the minimum string distance (default: 1)
+    pub substitution_cost: u32,
+    /// cost of an insertion or an omission in the minimum string distance (default: 1)
+    pub gap_cost: u32,
+    /// basis of the denominators of reported component probabilities
+    pub rate_normalization: RateNormalization,
+    /// distribution of substitutions over characters
+    pub substitution_model: SubstitutionModel,
+}
+
+impl Default for AlignmentOptions {
+    fn default() -> Self {
+        Self {
+            null_estimator: NullEstimator::default(),
+            selection: Selection::default(),
+            min_probability: 0.0,
+            max_cells: 10_000_000,
+            substitution_cost: 1,
+            gap_cost: 1,
+            rate_normalization: RateNormalization::default(),
+            substitution_model: SubstitutionModel::default(),
+        }
+    }
+}
+
+/// costs of edit operations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Costs {
+    substitution: u128,
+    gap: u128,
+}
+
+impl Costs {
+    pub(crate) const UNIT: Costs = Costs { substitution: 1, gap: 1 };
+
+    pub(crate) fn of(options: &AlignmentOptions) -> Self {
+        Self {
+            substitution: options.substitution_cost as u128,
+            gap: options.gap_cost as u128,
+        }
+    }
+}
+
+/// check that sequences can be aligned within the limit of matrix cells
+pub(crate) fn check_length<T>(presented: &[T], transcribed: &[T], options: &AlignmentOptions) -> Result<()> {
+    match presented.len().checked_mul(transcribed.len()) {
+        Some(cells) if cells <= options.max_cells => Ok(()),
+        _ => Err(TetError::InputTooLong { limit: options.max_cells }),
+    }
+}
+
+/// matrix of minimum string distances between prefixes of sequences
+///
+/// ref. https://dl.acm.org/doi/10.1145/572020.572056
+pub(crate) fn msd<T: PartialEq>(presented: &[T], transcribed: &[T]) -> Vec<Vec<u128>> {
+    msd_with_costs(presented, transcribed, Costs::UNIT)
+}
+
+/// matrix of minimum string distances weighted by costs of edit operations
+pub(crate) fn msd_with_costs<T: PartialEq>(presented: &[T], transcribed: &[T], costs: Costs) -> Vec<Vec<u128>> {
+    let mut d = Vec::new();
+    msd_into(presented, transcribed, costs, &mut d);
+
+    d
+}
+
+/// whether minimum string distances between sequences of the lengths are computed in `u16` cells
+///
+/// No distance exceeds the longer length times the larger cost,
+/// and a candidate of a cell exceeds it by at most one more cost.
+fn fits_u16(presented: usize, transcribed: usize, costs: Costs) -> bool {
+    let cost = costs.substitution.max(costs.gap);
+
+    (presented.max(transcribed) as u128 + 1) * cost <= u16::MAX as u128
+}
+
+/// cell of a matrix of minimum string distances
+trait Cell: Copy + Ord + Default + Add<Output=Self> + Into<u128> {
+    /// the cost in a cell, which must fit in it
+    fn of(cost: u128) -> Self;
+}
+
+impl Cell for u16 {
+    fn of(cost: u128) -> Self {
+        cost as u16
+    }
+}
+
+impl Cell for u128 {
+    fn of(cost: u128) -> Self {
+        cost
+    }
+}
+
+/// fill a matrix of minimum string distances, reusing its allocation
+///
+/// The matrix only grows, so cells out of `presented.len() + 1` rows
+/// and `transcribed.len() + 1` columns are left as they were.
+/// Costs must fit in cells of type `C`.
+fn msd_into<T: PartialEq, C: Cell>(presented: &[T], transcribed: &[T], costs: Costs, d: &mut Vec<Vec<C>>) {
+    let (substitution, gap) = (C::of(costs.substitution), C::of(costs.gap));
+    let r = |x: &T, y: &T| -> C {
+        if x == y { C::default() } else { substitution }
+    };
+
+    let (rows, columns) = (presented.len() + 1, transcribed.len() + 1);
+    if d.len() < rows {
+        d.resize(rows, Vec::new());
+    }
+    for row in d[..rows].iter_mut() {
+        if row.len() < columns {
+            row.resize(columns, C::default());
+        }
+    }
+
+    // i * gap and j * gap, accumulated to avoid converting indices into cells
+    d[0][0] = C::default();
+    for i in 1..rows {
+        d[i][0] = d[i - 1][0] + gap;
+    }
+
+    for j in 1..columns {
+        d[0][j] = d[0][j - 1] + gap;
+    }
+
+    for i in 1..=presented.len() {
+        for j in 1..=transcribed.len() {
+            let mut candidates = [
+                d[i - 1][j] + gap,
+                d[i][j - 1] + gap,
+                d[i - 1][j - 1] + r(&presented[i - 1], &transcribed[j - 1]),
+            ];
+            candidates.sort();
+            d[i][j] = candidates[0];
+        }
+    }
+}
+
+/// reusable buffer of the matrix of minimum string distances
+///
+/// Distances of sentence-length texts are kept in `u16` cells (see [`fits_u16`](fits_u16)),
+/// which shrinks the matrix, and the others in `u128` cells.
+///
+/// See [`TextEntryThroughput::calc_with_scratch`](crate::TextEntryThroughput::calc_with_scratch).
+#[derive(Debug, Clone, Default)]
+pub struct TetScratch {
+    narrow: Vec<Vec<u16>>,
+    wide: Vec<Vec<u128>>,
+}
+
+impl TetScratch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// fill the matrix of cells fitting sequences
+    pub(crate) fn fill<T: PartialEq>(&mut self, presented: &[T], transcribed: &[T], costs: Costs) -> Matrix<'_> {
+        if fits_u16(presented.len(), transcribed.len(), costs) {
+            msd_into(presented, transcribed, costs, &mut self.narrow);
+            Matrix::Narrow(&self.narrow)
         } else {
-            slf.len = slf.presented.len();
+            msd_into(presented, transcribed, costs, &mut self.wide);
+            Matrix::Wide(&self.wide)
         }
+    }
+}
 
-        slf.p_null = slf.p_null();
+/// filled matrix of minimum string distances of a scratch
+pub(crate) enum Matrix<'s> {
+    Narrow(&'s [Vec<u16>]),
+    Wide(&'s [Vec<u128>]),
+}
 
-        slf
+/// all optimal alignments of sequences, filling the matrix of the scratch
+///
+/// If there are more than `max_alignments` optimal alignments,
+/// only the alignment preferring diagonal moves, and `true`.
+pub(crate) fn all_alignments<T: Clone + PartialEq>(
+    presented: &[T],
+    transcribed: &[T],
+    costs: Costs,
+    scratch: &mut TetScratch,
+    max_alignments: Option<usize>,
+) -> (Vec<Alignment<T>>, bool) {
+    if presented == transcribed {
+        return (vec![identical_alignment(presented)], false);
     }
 
-    /// ref. https://dl.acm.org/doi/10.1145/572020.572056
-    fn msd(presented: &str, transcribed: &str) -> Vec<Vec<u128>> {
-        fn r(x: char, y: char) -> u128 {
-            if x == y { 0 } else { 1 }
+    match scratch.fill(presented, transcribed, costs) {
+        Matrix::Narrow(d) => alignments_along(presented, transcribed, d, costs, max_alignments),
+        Matrix::Wide(d) => alignments_along(presented, transcribed, d, costs, max_alignments),
+    }
+}
+
+/// all optimal alignments along a filled matrix, or the diagonal one beyond `max_alignments`
+fn alignments_along<T: Clone + PartialEq, C: Cell>(
+    presented: &[T],
+    transcribed: &[T],
+    d: &[Vec<C>],
+    costs: Costs,
+    max_alignments: Option<usize>,
+) -> (Vec<Alignment<T>>, bool) {
+    if let Some(max_alignments) = max_alignments {
+        if count_alignments(presented, transcribed, d, costs) > max_alignments as u128 {
+            // selecting the last alignment would enumerate all of them anyway
+            return (vec![diagonal_alignment(presented, transcribed, d, costs)], true);
         }
+    }
 
-        let mut d = std::iter::repeat(
-            std::iter::repeat(0u128)
-                .take(transcribed.chars().count() + 1)
-                .collect::<Vec<_>>()
-        )
-            .take(presented.chars().count() + 1)
-            .collect::<Vec<_>>();
+    let mut alignments = Vec::new();
+    trace_back(
+        presented,
+        transcribed,
+        d, costs, presented.len(), transcribed.len(),
+        Vec::new(),
+        Vec::new(),
+        &mut |alignment| alignments.push(alignment),
+    );
+
+    (alignments, false)
+}
+
+/// the only optimal alignment of identical sequences
+fn identical_alignment<T: Clone>(symbols: &[T]) -> Alignment<T> {
+    let aligned = symbols.iter()
+        .cloned()
+        .map(Element::Character)
+        .collect::<Vec<_>>();
+
+    Alignment {
+        presented: aligned.clone(),
+        transcribed: aligned,
+    }
+}
+
+/// ref. https://dl.acm.org/doi/fullHtml/10.1145/3290605.3300866
+#[allow(clippy::too_many_arguments)]
+fn trace_back<T: Clone + PartialEq, C: Cell, F: FnMut(Alignment<T>)>(
+    presented: &[T],
+    transcribed: &[T],
+    d: &[Vec<C>],
+    costs: Costs,
+    x: usize,
+    y: usize,
+    p_aligned: Vec<Element<T>>,
+    t_aligned: Vec<Element<T>>,
+    found: &mut F,
+)
+{
+    let (substitution, gap) = (C::of(costs.substitution), C::of(costs.gap));
+
+    if x == 0 && y == 0 {
+        found(Alignment {
+            presented: p_aligned,
+            transcribed: t_aligned,
+        });
+
+        return;
+    }
+
+    if x > 0 && y > 0 {
+        if d[x][y] == d[x - 1][y - 1] && presented[x - 1] == transcribed[y - 1] {
+            let (mut p_aligned, mut t_aligned) = (p_aligned.clone(), t_aligned.clone());
+            p_aligned.insert(0, Element::Character(presented[x - 1].clone()));
+            t_aligned.insert(0, Element::Character(transcribed[y - 1].clone()));
 
-        for i in 0..=presented.chars().count() {
-            d[i][0] = i as u128;
+            // recursive call
+            trace_back(presented, transcribed, d, costs, x - 1, y - 1, p_aligned, t_aligned, found);
         }
 
-        for j in 0..=transcribed.chars().count() {
-            d[0][j] = j as u128;
+        if d[x][y] == d[x - 1][y - 1] + substitution && presented[x - 1] != transcribed[y - 1] {
+            let (mut p_aligned, mut t_aligned) = (p_aligned.clone(), t_aligned.clone());
+            p_aligned.insert(0, Element::Character(presented[x - 1].clone()));
+            t_aligned.insert(0, Element::Character(transcribed[y - 1].clone()));
+
+            // recursive call
+            trace_back(presented, transcribed, d, costs, x - 1, y - 1, p_aligned, t_aligned, found);
         }
+    }
 
-        for i in 1..=presented.chars().count() {
-            for j in 1..=transcribed.chars().count() {
-                let mut candidates = [
-                    d[i - 1][j] + 1,
-                    d[i][j - 1] + 1,
-                    d[i - 1][j - 1] + r(
-                        presented.chars().skip(i - 1).next().unwrap(),
-                        transcribed.chars().skip(j - 1).next().unwrap(),
-                    )
-                ];
-                candidates.sort();
-                d[i][j] = candidates[0];
-            }
+    if x > 0 && d[x][y] == d[x - 1][y] + gap {
+        let (mut p_aligned, mut t_aligned) = (p_aligned.clone(), t_aligned.clone());
+        p_aligned.insert(0, Element::Character(presented[x - 1].clone()));
+        t_aligned.insert(0, Element::Null);
+
+        // recursive call
+        trace_back(presented, transcribed, d, costs, x - 1, y, p_aligned, t_aligned, found);
+    }
+
+    if y > 0 && d[x][y] == d[x][y - 1] + gap {
+        let (mut p_aligned, mut t_aligned) = (p_aligned.clone(), t_aligned.clone());
+        p_aligned.insert(0, Element::Null);
+        t_aligned.insert(0, Element::Character(transcribed[y - 1].clone()));
+
+        // recursive call
+        trace_back(presented, transcribed, d, costs, x, y - 1, p_aligned, t_aligned, found);
+    }
+}
+
+/// the last alignment found by `trace_back`, traced back without exploring the others
+///
+/// `trace_back` explores a diagonal move, an omission and an insertion in this order,
+/// and every optimal move leads back to the beginning of texts,
+/// so the last alignment takes an insertion whenever it is optimal, then an omission, then a diagonal move.
+fn last_alignment<T: Clone + PartialEq, C: Cell>(
+    presented: &[T],
+    transcribed: &[T],
+    d: &[Vec<C>],
+    costs: Costs,
+) -> Alignment<T> {
+    let gap = C::of(costs.gap);
+    let (mut x, mut y) = (presented.len(), transcribed.len());
+    let (mut p_aligned, mut t_aligned) = (Vec::new(), Vec::new());
+
+    while x > 0 || y > 0 {
+        if y > 0 && d[x][y] == d[x][y - 1] + gap {
+            p_aligned.push(Element::Null);
+            t_aligned.push(Element::Character(transcribed[y - 1].clone()));
+            y -= 1;
+        } else if x > 0 && (y == 0 || d[x][y] == d[x - 1][y] + gap) {
+            p_aligned.push(Element::Character(presented[x - 1].clone()));
+            t_aligned.push(Element::Null);
+            x -= 1;
+        } else if x > 0 {
+            p_aligned.push(Element::Character(presented[x - 1].clone()));
+            t_aligned.push(Element::Character(transcribed[y - 1].clone()));
+            x -= 1;
+            y -= 1;
+        } else {
+            p_aligned.push(Element::Null);
+            t_aligned.push(Element::Character(transcribed[y - 1].clone()));
+            y -= 1;
         }
+    }
+
+    p_aligned.reverse();
+    t_aligned.reverse();
+
+    Alignment {
+        presented: p_aligned,
+        transcribed: t_aligned,
+    }
+}
+
+/// trace back an optimal alignment, preferring diagonal moves
+fn diagonal_alignment<T: Clone + PartialEq, C: Cell>(
+    presented: &[T],
+    transcribed: &[T],
+    d: &[Vec<C>],
+    costs: Costs,
+) -> Alignment<T> {
+    let (substitution, gap) = (C::of(costs.substitution), C::of(costs.gap));
+    let (mut x, mut y) = (presented.len(), transcribed.len());
+    let (mut p_aligned, mut t_aligned) = (Vec::new(), Vec::new());
+
+    while x > 0 || y > 0 {
+        if x > 0 && y > 0 && (
+            (d[x][y] == d[x - 1][y - 1] && presented[x - 1] == transcribed[y - 1])
+                || (d[x][y] == d[x - 1][y - 1] + substitution && presented[x - 1] != transcribed[y - 1])
+        ) {
+            p_aligned.push(Element::Character(presented[x - 1].clone()));
+            t_aligned.push(Element::Character(transcribed[y - 1].clone()));
+            x -= 1;
+            y -= 1;
+        } else if x > 0 && d[x][y] == d[x - 1][y] + gap {
+            p_aligned.push(Element::Character(presented[x - 1].clone()));
+            t_aligned.push(Element::Null);
+            x -= 1;
+        } else {
+            p_aligned.push(Element::Null);
+            t_aligned.push(Element::Character(transcribed[y - 1].clone()));
+            y -= 1;
+        }
+    }
 
-        d
+    p_aligned.reverse();
+    t_aligned.reverse();
+
+    Alignment {
+        presented: p_aligned,
+        transcribed: t_aligned,
     }
+}
 
-    /// ref. https://dl.acm.org/doi/fullHtml/10.1145/3290605.3300866
-    fn alignments(
-        &mut self,
-        presented: &Vec<char>,
-        transcribed: &Vec<char>,
-        d: &mut Vec<Vec<u128>>,
-        x: usize,
-        y: usize,
-        p_aligned: Vec<Element>,
-        t_aligned: Vec<Element>,
-    )
-    {
-        if x == 0 && y == 0 {
-            self.presented = p_aligned;
-            self.transcribed = t_aligned;
+/// move of a traceback into a cell of a matrix of minimum string distances
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Move {
+    /// match or substitution
+    Diagonal,
+    Omission,
+    Insertion,
+}
 
-            return;
+/// an alignment of minimum distance weighted by fractional costs, preferring diagonal moves
+///
+/// Moves are recorded while distances are computed, and candidates within a relative tolerance
+/// of rounding errors are ties.
+/// Returns [`TetError::InvalidCost`](TetError::InvalidCost) for a negative or non-finite cost.
+pub(crate) fn weighted_alignment<C: Cost + ?Sized>(presented: &[char], transcribed: &[char], cost: &C) -> Result<Alignment> {
+    const EPSILON: f64 = 1e-9;
+
+    let checked = |cost: f64| -> Result<f64> {
+        if cost.is_finite() && cost >= 0.0 {
+            Ok(cost)
+        } else {
+            Err(TetError::InvalidCost(cost))
         }
+    };
+    let r = |x: char, y: char| -> Result<f64> {
+        if x == y { Ok(0.0) } else { checked(cost.substitution(x, y)) }
+    };
+    let gap = |c: char| checked(cost.gap(c));
+
+    let mut d = vec![vec![0f64; transcribed.len() + 1]; presented.len() + 1];
+    let mut moves = vec![vec![Move::Diagonal; transcribed.len() + 1]; presented.len() + 1];
+    for i in 1..=presented.len() {
+        d[i][0] = d[i - 1][0] + gap(presented[i - 1])?;
+        moves[i][0] = Move::Omission;
+    }
+    for j in 1..=transcribed.len() {
+        d[0][j] = d[0][j - 1] + gap(transcribed[j - 1])?;
+        moves[0][j] = Move::Insertion;
+    }
+    for i in 1..=presented.len() {
+        for j in 1..=transcribed.len() {
+            let diagonal = d[i - 1][j - 1] + r(presented[i - 1], transcribed[j - 1])?;
+            let omission = d[i - 1][j] + gap(presented[i - 1])?;
+            let insertion = d[i][j - 1] + gap(transcribed[j - 1])?;
+
+            let min = diagonal.min(omission).min(insertion);
+            let tolerance = EPSILON * min.max(1.0);
+            let (distance, step) = if diagonal - min <= tolerance {
+                (diagonal, Move::Diagonal)
+            } else if omission - min <= tolerance {
+                (omission, Move::Omission)
+            } else {
+                (insertion, Move::Insertion)
+            };
+            d[i][j] = distance;
+            moves[i][j] = step;
+        }
+    }
+
+    let (mut x, mut y) = (presented.len(), transcribed.len());
+    let (mut p_aligned, mut t_aligned) = (Vec::new(), Vec::new());
+
+    while x > 0 || y > 0 {
+        match moves[x][y] {
+            Move::Diagonal => {
+                p_aligned.push(Element::Character(presented[x - 1]));
+                t_aligned.push(Element::Character(transcribed[y - 1]));
+                x -= 1;
+                y -= 1;
+            }
+            Move::Omission => {
+                p_aligned.push(Element::Character(presented[x - 1]));
+                t_aligned.push(Element::Null);
+                x -= 1;
+            }
+            Move::Insertion => {
+                p_aligned.push(Element::Null);
+                t_aligned.push(Element::Character(transcribed[y - 1]));
+                y -= 1;
+            }
+        }
+    }
 
-        if x > 0 && y > 0 {
-            if d[x][y] == d[x - 1][y - 1] && presented[x - 1] == transcribed[y - 1] {
-                let (mut p_aligned, mut t_aligned) = (p_aligned.clone(), t_aligned.clone());
-                p_aligned.insert(0, Element::Character(presented[x - 1]));
-                t_aligned.insert(0, Element::Character(transcribed[y - 1]));
+    p_aligned.reverse();
+    t_aligned.reverse();
 
-                // recursive call
-                self.alignments(presented, transcribed, d, x - 1, y - 1, p_aligned, t_aligned);
+    Ok(Alignment {
+        presented: p_aligned,
+        transcribed: t_aligned,
+    })
+}
+
+/// number of optimal alignments, traced back in the same way as `trace_back`
+fn count_alignments<T: PartialEq, C: Cell>(presented: &[T], transcribed: &[T], d: &[Vec<C>], costs: Costs) -> u128 {
+    let (substitution, gap) = (C::of(costs.substitution), C::of(costs.gap));
+    let mut n = vec![vec![0u128; transcribed.len() + 1]; presented.len() + 1];
+    n[0][0] = 1;
+
+    for x in 0..=presented.len() {
+        for y in 0..=transcribed.len() {
+            if x > 0 && y > 0 && (
+                (d[x][y] == d[x - 1][y - 1] && presented[x - 1] == transcribed[y - 1])
+                    || (d[x][y] == d[x - 1][y - 1] + substitution && presented[x - 1] != transcribed[y - 1])
+            ) {
+                n[x][y] = n[x][y].saturating_add(n[x - 1][y - 1]);
             }
 
-            if d[x][y] == d[x - 1][y - 1] + 1 {
-                let (mut p_aligned, mut t_aligned) = (p_aligned.clone(), t_aligned.clone());
-                p_aligned.insert(0, Element::Character(presented[x - 1]));
-                t_aligned.insert(0, Element::Character(transcribed[y - 1]));
+            if x > 0 && d[x][y] == d[x - 1][y] + gap {
+                n[x][y] = n[x][y].saturating_add(n[x - 1][y]);
+            }
 
-                // recursive call
-                self.alignments(presented, transcribed, d, x - 1, y - 1, p_aligned, t_aligned);
+            if y > 0 && d[x][y] == d[x][y - 1] + gap {
+                n[x][y] = n[x][y].saturating_add(n[x][y - 1]);
             }
         }
+    }
 
-        if x > 0 && d[x][y] == d[x - 1][y] + 1 {
-            let (mut p_aligned, mut t_aligned) = (p_aligned.clone(), t_aligned.clone());
-            p_aligned.insert(0, Element::Character(presented[x - 1]));
-            t_aligned.insert(0, Element::Null);
+    n[presented.len()][transcribed.len()]
+}
 
-            // recursive call
-            self.alignments(presented, transcribed, d, x - 1, y, p_aligned, t_aligned);
+/// intermediate values of text entry throughput
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intermediates {
+    /// H(X): bits/character
+    pub hx: f64,
+    /// H_Y(X): bits/character
+    pub hyx: f64,
+    /// I(X,Y): bits/character
+    pub ixy: f64,
+    /// p(NULL)
+    pub p_null: f64,
+    /// p(I)
+    pub insertion_probability: f64,
+    /// p(M)
+    pub omission_probability: f64,
+    /// p(S)
+    pub substitution_probability: f64,
+    /// p(C)
+    pub probability_of_correct_entries: f64,
+}
+
+impl Intermediates {
+    /// whether every value differs by at most `tol`
+    pub fn approx_eq(&self, other: &Intermediates, tol: f64) -> bool {
+        approx_eq(self.hx, other.hx, tol)
+            && approx_eq(self.hyx, other.hyx, tol)
+            && approx_eq(self.ixy, other.ixy, tol)
+            && approx_eq(self.p_null, other.p_null, tol)
+            && approx_eq(self.insertion_probability, other.insertion_probability, tol)
+            && approx_eq(self.omission_probability, other.omission_probability, tol)
+            && approx_eq(self.substitution_probability, other.substitution_probability, tol)
+            && approx_eq(self.probability_of_correct_entries, other.probability_of_correct_entries, tol)
+    }
+}
+
+/// optimal alignment of presented and transcribed texts
+///
+/// ref. https://dl.acm.org/doi/fullHtml/10.1145/3290605.3300866
+#[derive(Debug, PartialEq)]
+pub struct OptimalAlignments<'a, D: Source = Distribution> {
+    distribution: &'a D,
+    presented: Vec<Element<D::Symbol>>,
+    transcribed: Vec<Element<D::Symbol>>,
+    p_null: f64,
+    len: usize,
+    min_probability: f64,
+    rate_normalization: RateNormalization,
+    substitution_model: SubstitutionModel,
+}
+
+impl<'a> OptimalAlignments<'a> {
+    pub fn new(presented: &str, transcribed: &str, distribution: &'a Distribution) -> Result<Self> {
+        Self::with_options(presented, transcribed, distribution, &AlignmentOptions::default())
+    }
+
+    pub fn with_options(
+        presented: &str,
+        transcribed: &str,
+        distribution: &'a Distribution,
+        options: &AlignmentOptions,
+    ) -> Result<Self> {
+        Self::with_scratch(presented, transcribed, distribution, options, &mut TetScratch::new())
+    }
+
+    /// align texts, reusing the matrix of the scratch
+    pub(crate) fn with_scratch(
+        presented: &str,
+        transcribed: &str,
+        distribution: &'a Distribution,
+        options: &AlignmentOptions,
+        scratch: &mut TetScratch,
+    ) -> Result<Self> {
+        let (presented, transcribed): (Vec<char>, Vec<char>) = (
+            presented.chars().collect(),
+            transcribed.chars().collect()
+        );
+
+        Self::from_symbols_in(&presented, &transcribed, distribution, options, scratch)
+    }
+
+    /// matrix of minimum string distances between prefixes of texts, weighted by costs of `options`
+    ///
+    /// `d[i][j]` is the distance between the first `i` presented and the first `j` transcribed characters.
+    pub fn msd_matrix(presented: &str, transcribed: &str, options: &AlignmentOptions) -> Vec<Vec<u128>> {
+        let (presented, transcribed): (Vec<char>, Vec<char>) = (
+            presented.chars().collect(),
+            transcribed.chars().collect()
+        );
+
+        msd_with_costs(&presented, &transcribed, Costs::of(options))
+    }
+
+    /// align texts along a matrix of minimum string distances computed beforehand
+    ///
+    /// The matrix must be the one of [`msd_matrix`](OptimalAlignments::msd_matrix) with the same texts and options,
+    /// so that it is not computed again.
+    /// Dimensions of the matrix are checked only in debug builds.
+    pub fn with_precomputed_msd(
+        presented: &str,
+        transcribed: &str,
+        distribution: &'a Distribution,
+        options: &AlignmentOptions,
+        d: &[Vec<u128>],
+    ) -> Result<Self> {
+        let (presented, transcribed): (Vec<char>, Vec<char>) = (
+            presented.chars().collect(),
+            transcribed.chars().collect()
+        );
+        check_length(&presented, &transcribed, options)?;
+
+        debug_assert_eq!(d.len(), presented.len() + 1, "rows of the matrix differ from the presented text");
+        debug_assert!(
+            d.iter().all(|row| row.len() == transcribed.len() + 1),
+            "columns of the matrix differ from the transcribed text",
+        );
+
+        Self::from_matrix(&presented, &transcribed, distribution, options, d)
+    }
+
+    /// align texts with costs of a substitution and of an insertion or an omission
+    ///
+    /// `sub = 1, gap = 1` is the same as [`new`](OptimalAlignments::new).
+    pub fn with_costs(
+        presented: &str,
+        transcribed: &str,
+        distribution: &'a Distribution,
+        sub: u32,
+        gap: u32,
+    ) -> Result<Self> {
+        let options = AlignmentOptions {
+            substitution_cost: sub,
+            gap_cost: gap,
+            ..AlignmentOptions::default()
+        };
+
+        Self::with_options(presented, transcribed, distribution, &options)
+    }
+
+    /// align texts with fractional costs of edit operations
+    ///
+    /// Unlike integer costs of [`AlignmentOptions`](AlignmentOptions),
+    /// minimum string distances are `f64`, and the alignment found by
+    /// preferring diagonal moves is evaluated regardless of `options.selection`.
+    /// A negative or non-finite cost is [`TetError::InvalidCost`](TetError::InvalidCost).
+    pub fn with_cost<C: Cost + ?Sized>(
+        presented: &str,
+        transcribed: &str,
+        distribution: &'a Distribution,
+        cost: &C,
+        options: &AlignmentOptions,
+    ) -> Result<Self> {
+        let (presented, transcribed): (Vec<char>, Vec<char>) = (
+            presented.chars().collect(),
+            transcribed.chars().collect()
+        );
+        check_length(&presented, &transcribed, options)?;
+
+        let alignment = weighted_alignment(&presented, &transcribed, cost)?;
+
+        Self::from_alignment(alignment, distribution, options)
+    }
+
+    /// ref. https://dl.acm.org/doi/10.1145/572020.572056
+    #[cfg(test)]
+    fn msd(presented: &str, transcribed: &str) -> Vec<Vec<u128>> {
+        let (presented, transcribed): (Vec<char>, Vec<char>) = (
+            presented.chars().collect(),
+            transcribed.chars().collect()
+        );
+
+        msd(&presented, &transcribed)
+    }
+}
+
+impl<'a, D: Source> OptimalAlignments<'a, D> {
+    /// align sequences of symbols
+    ///
+    /// Returns [`TetError::InputTooLong`](TetError::InputTooLong) before aligning
+    /// if sequences exceed [`AlignmentOptions::max_cells`](AlignmentOptions::max_cells).
+    pub fn from_symbols(
+        presented: &[D::Symbol],
+        transcribed: &[D::Symbol],
+        distribution: &'a D,
+        options: &AlignmentOptions,
+    ) -> Result<Self> {
+        Self::from_symbols_in(presented, transcribed, distribution, options, &mut TetScratch::new())
+    }
+
+    /// align sequences of symbols, filling the matrix of minimum string distances of the scratch
+    pub(crate) fn from_symbols_in(
+        presented: &[D::Symbol],
+        transcribed: &[D::Symbol],
+        distribution: &'a D,
+        options: &AlignmentOptions,
+        scratch: &mut TetScratch,
+    ) -> Result<Self> {
+        check_length(presented, transcribed, options)?;
+
+        // no matrix is needed for a perfect transcription
+        if presented == transcribed {
+            return Self::from_alignment(identical_alignment(presented), distribution, options);
         }
 
-        if y > 0 && d[x][y] == d[x][y - 1] + 1 {
-            let (mut p_aligned, mut t_aligned) = (p_aligned.clone(), t_aligned.clone());
-            p_aligned.insert(0, Element::Null);
-            t_aligned.insert(0, Element::Character(transcribed[y - 1]));
+        match scratch.fill(presented, transcribed, Costs::of(options)) {
+            Matrix::Narrow(d) => Self::from_matrix(presented, transcribed, distribution, options, d),
+            Matrix::Wide(d) => Self::from_matrix(presented, transcribed, distribution, options, d),
+        }
+    }
 
-            // recursive call
-            self.alignments(presented, transcribed, d, x, y - 1, p_aligned, t_aligned);
+    /// align sequences of symbols along the filled matrix `d` of minimum string distances
+    fn from_matrix<C: Cell>(
+        presented: &[D::Symbol],
+        transcribed: &[D::Symbol],
+        distribution: &'a D,
+        options: &AlignmentOptions,
+        d: &[Vec<C>],
+    ) -> Result<Self> {
+        let costs = Costs::of(options);
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            presented = presented.len(),
+            transcribed = transcribed.len(),
+            msd = Into::<u128>::into(d[presented.len()][transcribed.len()]) as u64,
+            alignments = count_alignments(presented, transcribed, d, costs) as u64,
+            "optimal alignments",
+        );
+
+        let alignment = match options.selection {
+            Selection::Last => {
+                last_alignment(presented, transcribed, d, costs)
+            }
+            Selection::PreferDiagonal => {
+                diagonal_alignment(presented, transcribed, d, costs)
+            }
+        };
+
+        Self::from_alignment(alignment, distribution, options)
+    }
+
+    /// evaluate an alignment
+    ///
+    /// Returns [`TetError::InvalidAlignment`](TetError::InvalidAlignment) if rows differ in length.
+    pub(crate) fn from_alignment(
+        alignment: Alignment<D::Symbol>,
+        distribution: &'a D,
+        options: &AlignmentOptions,
+    ) -> Result<Self> {
+        let mut slf = Self {
+            distribution,
+            presented: alignment.presented,
+            transcribed: alignment.transcribed,
+            p_null: 0.0,
+            len: 0,
+            min_probability: options.min_probability,
+            rate_normalization: options.rate_normalization,
+            substitution_model: options.substitution_model,
+        };
+
+        if slf.presented.len() != slf.transcribed.len() {
+            return Err(TetError::InvalidAlignment);
         }
+        slf.len = slf.presented.len();
+
+        slf.p_null = slf.estimate_p_null(options.null_estimator);
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            p_null = slf.p_null,
+            insertion = slf.insertion_probability(),
+            omission = slf.omission_probability(),
+            substitution = slf.substitution_probability(),
+            correct = slf.probability_of_correct_entries(),
+            "component probabilities",
+        );
 
-        return;
+        Ok(slf)
+    }
+
+    /// the evaluated alignment
+    pub fn alignment(&self) -> Alignment<D::Symbol> {
+        Alignment {
+            presented: self.presented.clone(),
+            transcribed: self.transcribed.clone(),
+        }
     }
 
     /// N(presented -> entry)
-    fn n<F: Fn(&Element, &Element) -> bool>(&self, f: F) -> usize {
+    fn n<F: Fn(&Element<D::Symbol>, &Element<D::Symbol>) -> bool>(&self, f: F) -> usize {
         let mut counter = 0usize;
 
         self.presented.iter()
@@ -176,22 +1016,160 @@ impl<'a> OptimalAlignments<'a> {
         counter
     }
 
+    /// p(NULL) = p'(NULL)
+    fn estimate_p_null(&self, estimator: NullEstimator) -> f64 {
+        match estimator {
+            NullEstimator::PresentedFraction => {
+                self.n(|p, _| p.is_null()) as f64
+                    / self.len() as f64
+            }
+            NullEstimator::AllPositions => {
+                self.n(|p, t| p.is_null() || t.is_null()) as f64
+                    / (2 * self.len()) as f64
+            }
+            NullEstimator::Fixed(p_null) => p_null,
+        }
+    }
+
+    /// model of the channel from presented to transcribed characters
+    fn channel(&self) -> Channel<'a, D> {
+        Channel {
+            distribution: self.distribution,
+            p_null: self.p_null,
+            rates: self.normalized_rates(RateNormalization::AlignedLength),
+            min_probability: self.min_probability,
+            substitution_model: self.substitution_model,
+        }
+    }
+
+    /// H_Y(X), undefined for an empty alignment
+    fn hyx(&self) -> Option<f64> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.channel().hyx()
+    }
+
+    /// I(X,Y): bits/character
+    pub fn ixy(&self) -> Option<f64> {
+        // H_Y(X) = 0 without errors, but undefined without any character
+        let hyx = if self.len > 0 && self.n_correct() == self.len {
+            0.0
+        } else {
+            self.hyx()?
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(hx = self.distribution.hx(), hyx, "mutual information");
+
+        Some(self.distribution.hx() - hyx)
+    }
+
+    /// intermediate values, e.g. for assertions with tolerance
+    pub fn intermediates(&self) -> Option<Intermediates> {
+        let hx = self.distribution.hx();
+        let ixy = self.ixy()?;
+
+        Some(Intermediates {
+            hx,
+            hyx: hx - ixy,
+            ixy,
+            p_null: self.p_null,
+            insertion_probability: self.insertion_probability(),
+            omission_probability: self.omission_probability(),
+            substitution_probability: self.substitution_probability(),
+            probability_of_correct_entries: self.probability_of_correct_entries(),
+        })
+    }
+
+    /// pointwise information of each aligned position (bits)
+    ///
+    /// A pair of presented i and transcribed j contributes log2(p_j(i) / p(i)),
+    /// i.e. how much seeing j raises the probability of i.
+    /// Correct entries contribute positively, substitutions mostly negatively,
+    /// and omissions and insertions nothing.
+    /// I(X,Y) is the expectation of this decomposition under the model,
+    /// so the sum over positions approximates I(X,Y) times the aligned length.
+    pub fn per_position_information(&self) -> Option<Vec<f64>> {
+        let channel = self.channel();
+
+        self.presented.iter()
+            .zip(self.transcribed.iter())
+            .map(|(i, j)| {
+                if i.is_null() {
+                    return Some(0.0);
+                }
+
+                let p_j_i = channel.p_j_i(i, j)?.max(self.min_probability);
+                Some(p_j_i.log2() - channel.p(i)?.log2())
+            })
+            .collect()
+    }
+
+    /// \sum_{i,j} N(i -> j)
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// rates of edit operations, summing up to 1
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErrorRates {
+    /// p(I)
+    pub insertion: f64,
+    /// p(M)
+    pub omission: f64,
+    /// p(S)
+    pub substitution: f64,
+    /// p(C)
+    pub correct: f64,
+}
+
+impl ErrorRates {
+    /// check that every rate is in [0, 1] and they sum up to 1
+    pub(crate) fn validate(&self) -> Result<()> {
+        let rates = [self.insertion, self.omission, self.substitution, self.correct];
+
+        let in_range = rates.iter().all(|rate| (0.0..=1.0).contains(rate));
+        if in_range && approx_eq(rates.iter().sum(), 1.0, 1e-6) {
+            Ok(())
+        } else {
+            Err(TetError::InvalidErrorRates)
+        }
+    }
+
+    /// p(NULL) estimated from the rates
+    pub(crate) fn p_null(&self, estimator: NullEstimator) -> f64 {
+        match estimator {
+            NullEstimator::PresentedFraction => self.insertion,
+            NullEstimator::AllPositions => (self.insertion + self.omission) / 2.0,
+            NullEstimator::Fixed(p_null) => p_null,
+        }
+    }
+}
+
+/// model of the channel from presented to transcribed characters
+pub(crate) struct Channel<'a, D: Source> {
+    pub(crate) distribution: &'a D,
+    pub(crate) p_null: f64,
+    pub(crate) rates: ErrorRates,
+    pub(crate) min_probability: f64,
+    pub(crate) substitution_model: SubstitutionModel,
+}
+
+impl<'a, D: Source> Channel<'a, D> {
     /// p(i)
-    fn p(&self, c: &Element) -> Option<f64> {
+    fn p(&self, c: &Element<D::Symbol>) -> Option<f64> {
         match c {
             Element::Null => Some(self.p_null),
             Element::Character(c) => self.distribution.p(c).cloned()
         }
     }
 
-    /// p(NULL) = p'(NULL)
-    fn p_null(&self) -> f64 {
-        self.n(|p, _| p == &Element::Null) as f64
-            / self.len() as f64
-    }
-
     /// p'(c)
-    fn p_dash(&self, c: &Element) -> Option<f64> {
+    fn p_dash(&self, c: &Element<D::Symbol>) -> Option<f64> {
         match c {
             Element::Null => Some(self.p_null),
             c => {
@@ -204,22 +1182,40 @@ impl<'a> OptimalAlignments<'a> {
     }
 
     /// p_i(j)
-    fn p_i_j(&self, i: &Element, j: &Element) -> f64 {
+    fn p_i_j(&self, i: &Element<D::Symbol>, j: &Element<D::Symbol>) -> f64 {
         // insertion error
         match (i, j) {
             (Element::Null, Element::Character(_)) => {
-                self.insertion_probability()
-                    / self.distribution.map.keys().count() as f64
+                self.rates.insertion
+                    / self.distribution.symbols().count() as f64
             }
             (Element::Character(_), Element::Null) => {
-                self.omission_probability()
+                self.rates.omission
             }
             (Element::Character(p), Element::Character(e)) => {
                 if p != e {
-                    self.substitution_probability()
-                        / (self.distribution.map.keys().count() - 1) as f64
+                    match self.substitution_model {
+                        SubstitutionModel::Uniform => {
+                            // a substitution of a single-symbol source is never expected
+                            let others = self.distribution.symbols().count().saturating_sub(1);
+                            if others == 0 {
+                                0.0
+                            } else {
+                                self.rates.substitution / others as f64
+                            }
+                        }
+                        SubstitutionModel::Proportional => {
+                            let p_e = self.distribution.p(e).cloned().unwrap_or(0.0);
+                            let others = 1.0 - self.distribution.p(p).cloned().unwrap_or(0.0);
+                            if others > 0.0 {
+                                self.rates.substitution * p_e / others
+                            } else {
+                                0.0
+                            }
+                        }
+                    }
                 } else {
-                    self.probability_of_correct_entries()
+                    self.rates.correct
                 }
             }
             _ => {
@@ -230,7 +1226,7 @@ impl<'a> OptimalAlignments<'a> {
     }
 
     /// p(i,j)
-    fn pij(&self, i: &Element, j: &Element) -> Option<f64> {
+    fn pij(&self, i: &Element<D::Symbol>, j: &Element<D::Symbol>) -> Option<f64> {
         self.p_dash(i)
             .map(|p_dash_i| {
                 p_dash_i * self.p_i_j(i, j)
@@ -238,29 +1234,23 @@ impl<'a> OptimalAlignments<'a> {
     }
 
     /// p_j(i)
-    fn p_j_i(&self, i: &Element, j: &Element) -> Option<f64> {
+    fn p_j_i(&self, i: &Element<D::Symbol>, j: &Element<D::Symbol>) -> Option<f64> {
         // let extend = vec![Element::Null];
         Some(
             self.pij(i, j)?
-                / self.distribution.map.keys()
+                / self.distribution.symbols()
                 .cloned()
                 .map(Element::Character)
                 // .chain(extend)
                 // .filter(|i| !i.is_null() || !j.is_null())
                 .map(|i| self.pij(&i, j))
-                .fold(Some(0.0), |acc, p| {
-                    if acc.is_none() || p.is_none() {
-                        None
-                    } else {
-                        Some(acc.unwrap() + p.unwrap())
-                    }
-                })?
+                .try_fold(0.0, |acc, p| p.map(|p| acc + p))?
         )
     }
 
     /// H_Y(X)
-    fn hyx(&self) -> Option<f64> {
-        let elements = self.distribution.map.keys()
+    pub(crate) fn hyx(&self) -> Option<f64> {
+        let elements = self.distribution.symbols()
             .cloned()
             .map(Element::Character);
 
@@ -277,69 +1267,143 @@ impl<'a> OptimalAlignments<'a> {
                     continue;
                 }
                 // dbg!((&i, &j));
-                acc += self.pij(&i, &j)?
-                    * self.p_j_i(&i, &j)?.log2();
+                let pij = self.pij(&i, &j)?;
+                // 0 log 0 = 0
+                if pij > 0.0 {
+                    acc += pij * self.p_j_i(&i, &j)?.max(self.min_probability).log2();
+                }
                 // dbg!(&acc);
             }
         }
 
         Some(-acc)
     }
+}
 
-    /// I(X,Y): bits/character
-    pub fn ixy(&self) -> Option<f64> {
-        self.hyx()
-            .map(|hyx| self.distribution.hx() - hyx)
+impl<'a, D: Source> OptimalAlignments<'a, D> {
+    /// set a floor of probabilities taken logarithms of
+    ///
+    /// This is a guard for numerical stability:
+    /// tiny probabilities are clamped to `min_probability` before `log2`
+    /// so that they don't dominate H_Y(X).
+    /// The default floor is 0, i.e. no clamping.
+    pub fn min_probability(mut self, min_probability: f64) -> Self {
+        self.min_probability = min_probability;
+        self
     }
 
-    /// \sum_{i,j} N(i -> j)
-    fn len(&self) -> usize {
+    /// p(NULL)
+    pub fn p_null(&self) -> f64 {
+        self.p_null
+    }
+
+    /// N(NULL -> c): number of inserted characters
+    pub fn n_insertions(&self) -> usize {
+        self.n(|p, e| p.is_null() && !e.is_null())
+    }
+
+    /// N(c -> NULL): number of omitted characters
+    pub fn n_omissions(&self) -> usize {
+        self.n(|p, e| !p.is_null() && e.is_null())
+    }
+
+    /// N(c -> c'): number of substituted characters
+    pub fn n_substitutions(&self) -> usize {
+        self.n(|p, e| !p.is_null() && !e.is_null() && p != e)
+    }
+
+    /// N(c -> c): number of correctly entered characters
+    pub fn n_correct(&self) -> usize {
+        self.n(|p, e| !p.is_null() && !e.is_null() && p == e)
+    }
+
+    /// p(I), p(M), p(S) and p(C)
+    ///
+    /// They sum up to 1 only with [`RateNormalization::AlignedLength`](RateNormalization::AlignedLength).
+    pub fn error_rates(&self) -> ErrorRates {
+        self.normalized_rates(self.rate_normalization)
+    }
+
+    /// \sum_{i,j} N(i -> j): length of the alignment
+    pub fn aligned_length(&self) -> usize {
         self.len
     }
 }
 
-impl<'a> OptimalAlignments<'a> {
+impl<'a, D: Source> OptimalAlignments<'a, D> {
     /// p(I)
     pub fn insertion_probability(&self) -> f64 {
-        let closure = |p: &Element, e: &Element| -> bool {
-            p.is_null() && !e.is_null()
-        };
-
-        self.n(closure) as f64
-            / self.len as f64
+        self.insertion_probability_in(self.rate_normalization)
     }
 
     /// p(M)
     pub fn omission_probability(&self) -> f64 {
-        let closure = |p: &Element, e: &Element| -> bool {
+        let closure = |p: &Element<D::Symbol>, e: &Element<D::Symbol>| -> bool {
             !p.is_null() && e.is_null()
         };
 
-        self.n(closure) as f64
-            / self.n(|p, _| !p.is_null()) as f64
-            * (1f64 - self.insertion_probability())
+        self.rate(self.n(closure), self.rate_normalization)
     }
 
     /// p(S)
     pub fn substitution_probability(&self) -> f64 {
-        let closure = |p: &Element, e: &Element| -> bool {
+        let closure = |p: &Element<D::Symbol>, e: &Element<D::Symbol>| -> bool {
             !p.is_null() && !e.is_null() && p != e
         };
 
-        self.n(closure) as f64
-            / self.n(|p, _| !p.is_null()) as f64
-            * (1f64 - self.insertion_probability())
+        self.rate(self.n(closure), self.rate_normalization)
     }
 
     /// p(C)
     pub fn probability_of_correct_entries(&self) -> f64 {
-        let closure = |p: &Element, e: &Element| -> bool {
+        let closure = |p: &Element<D::Symbol>, e: &Element<D::Symbol>| -> bool {
             !p.is_null() && !e.is_null() && p == e
         };
 
-        self.n(closure) as f64
-            / self.n(|p, _| !p.is_null()) as f64
-            * (1f64 - self.insertion_probability())
+        self.rate(self.n(closure), self.rate_normalization)
+    }
+
+    /// p(I), p(M), p(S) and p(C) under the normalization
+    fn normalized_rates(&self, normalization: RateNormalization) -> ErrorRates {
+        ErrorRates {
+            insertion: self.insertion_probability_in(normalization),
+            omission: self.rate(self.n_omissions(), normalization),
+            substitution: self.rate(self.n_substitutions(), normalization),
+            correct: self.rate(self.n_correct(), normalization),
+        }
+    }
+
+    /// p(I) under the normalization
+    fn insertion_probability_in(&self, normalization: RateNormalization) -> f64 {
+        let n = self.n_insertions() as f64;
+        match normalization {
+            RateNormalization::AlignedLength => n / self.len as f64,
+            RateNormalization::PresentedLength => n / self.n_presented() as f64,
+            RateNormalization::TranscribedLength => n / self.n_transcribed() as f64,
+        }
+    }
+
+    /// probability of edit operations other than insertions
+    fn rate(&self, n: usize, normalization: RateNormalization) -> f64 {
+        match normalization {
+            RateNormalization::AlignedLength => {
+                n as f64
+                    / self.n_presented() as f64
+                    * (1f64 - self.insertion_probability_in(normalization))
+            }
+            RateNormalization::PresentedLength => n as f64 / self.n_presented() as f64,
+            RateNormalization::TranscribedLength => n as f64 / self.n_transcribed() as f64,
+        }
+    }
+
+    /// N_p: number of presented characters
+    fn n_presented(&self) -> usize {
+        self.n(|p, _| !p.is_null())
+    }
+
+    /// N_t: number of transcribed characters
+    fn n_transcribed(&self) -> usize {
+        self.n(|_, e| !e.is_null())
     }
 }
 
@@ -365,13 +1429,283 @@ mod test {
         assert_eq!(d[7][8], 3)
     }
 
+    #[test]
+    fn msd_u16_test() {
+        let (presented, transcribed): (Vec<char>, Vec<char>) = (
+            "abcd".chars().collect(),
+            "acbd".chars().collect()
+        );
+        let mut d: Vec<Vec<u16>> = Vec::new();
+        msd_into(&presented, &transcribed, Costs::UNIT, &mut d);
+        let answer = vec![
+            vec![0, 1, 2, 3, 4],
+            vec![1, 0, 1, 2, 3],
+            vec![2, 1, 1, 1, 2],
+            vec![3, 2, 1, 2, 2],
+            vec![4, 3, 2, 2, 2],
+        ];
+        assert_eq!(d, answer);
+
+        let (presented, transcribed): (Vec<char>, Vec<char>) = (
+            "quickly".chars().collect(),
+            "qucehkly".chars().collect()
+        );
+        let costs = Costs { substitution: 1, gap: 2 };
+        let mut narrow: Vec<Vec<u16>> = Vec::new();
+        msd_into(&presented, &transcribed, costs, &mut narrow);
+        let wide = msd_with_costs(&presented, &transcribed, costs);
+        assert!(fits_u16(presented.len(), transcribed.len(), costs));
+        assert_eq!(
+            narrow.iter().map(|row| row.iter().map(|&c| c as u128).collect::<Vec<_>>()).collect::<Vec<_>>(),
+            wide,
+        );
+
+        // traced back in the same way
+        for &max_alignments in [None, Some(1)].iter() {
+            assert_eq!(
+                alignments_along(&presented, &transcribed, &narrow, costs, max_alignments),
+                alignments_along(&presented, &transcribed, &wide, costs, max_alignments),
+            );
+        }
+        assert_eq!(
+            count_alignments(&presented, &transcribed, &narrow, costs),
+            count_alignments(&presented, &transcribed, &wide, costs),
+        );
+    }
+
+    #[test]
+    fn msd_u16_boundary_test() {
+        // inputs shorter than u16::MAX
+        let limit = u16::MAX as usize - 1;
+        assert!(fits_u16(limit, 1, Costs::UNIT));
+        assert!(!fits_u16(limit + 1, 1, Costs::UNIT));
+        assert!(!fits_u16(limit / 2 + 1, 1, Costs { substitution: 1, gap: 2 }));
+        assert!(!fits_u16(0, 0, Costs { substitution: 1, gap: u16::MAX as u128 + 1 }));
+
+        // the largest input of u16 cells
+        let mut scratch = TetScratch::new();
+        match scratch.fill(&vec!['a'; limit], &['b'], Costs::UNIT) {
+            Matrix::Narrow(d) => {
+                assert_eq!(d[limit][1], limit as u16);
+                assert_eq!(d[limit][0], limit as u16);
+            }
+            Matrix::Wide(_) => panic!("u16 cells are expected"),
+        }
+
+        // falls back to wider cells
+        match scratch.fill(&vec!['a'; limit + 1], &['b'], Costs::UNIT) {
+            Matrix::Wide(d) => assert_eq!(d[limit + 1][1], limit as u128 + 1),
+            Matrix::Narrow(_) => panic!("u128 cells are expected"),
+        }
+        assert_eq!(msd(&['b'], &vec!['a'; limit + 2])[1][limit + 2], u16::MAX as u128 + 1);
+    }
+
+    #[test]
+    fn msd_words_test() {
+        let d = msd(&["the", "quick", "fox"], &["the", "quik", "fox"]);
+        assert_eq!(d[3][3], 1);
+
+        let d = msd::<&str>(&[], &["fox"]);
+        assert_eq!(d, vec![vec![0, 1]]);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn count_alignments_test() {
+        let (presented, transcribed): (Vec<char>, Vec<char>) = (
+            "abcd".chars().collect(),
+            "acbd".chars().collect()
+        );
+        let d = msd(&presented, &transcribed);
+
+        // a[c]b_d, a_cbd, ac[b]d... with distance 2
+        assert_eq!(count_alignments(&presented, &transcribed, &d, Costs::UNIT), 3);
+    }
+
+    #[test]
+    fn last_alignment_test() {
+        for &(presented, transcribed) in [("quickly", "qucehkly"), ("abcd", "acbd"), ("aaaaaaaaaa", "aaaaa"), ("", "ab"), ("ab", "")].iter() {
+            let (presented, transcribed): (Vec<char>, Vec<char>) = (
+                presented.chars().collect(),
+                transcribed.chars().collect()
+            );
+            for &costs in [Costs::UNIT, Costs { substitution: 1, gap: 2 }, Costs { substitution: 3, gap: 1 }].iter() {
+                let d = msd_with_costs(&presented, &transcribed, costs);
+                let mut last = None;
+                trace_back(
+                    &presented, &transcribed, &d, costs, presented.len(), transcribed.len(),
+                    Vec::new(), Vec::new(), &mut |alignment| last = Some(alignment),
+                );
+                assert_eq!(Some(last_alignment(&presented, &transcribed, &d, costs)), last);
+            }
+        }
+    }
+
+    #[test]
+    fn operations_test() {
+        let distribution = alphabet_distribution();
+        let last = OptimalAlignments::new("quickly", "qucehkly", &distribution).unwrap()
+            .alignment();
+
+        assert_eq!(last.operations(), vec![
+            Operation::Match('q'), Operation::Match('u'),
+            Operation::Omission('i'),
+            Operation::Match('c'),
+            Operation::Insertion('e'), Operation::Insertion('h'),
+            Operation::Match('k'), Operation::Match('l'), Operation::Match('y'),
+        ]);
+
+        let options = AlignmentOptions { selection: Selection::PreferDiagonal, ..AlignmentOptions::default() };
+        let diagonal = OptimalAlignments::with_options("quickly", "qucehkly", &distribution, &options).unwrap()
+            .alignment();
+
+        assert_eq!(diagonal.operations(), vec![
+            Operation::Match('q'), Operation::Match('u'),
+            Operation::Insertion('c'),
+            Operation::Substitution { expected: 'i', got: 'e' },
+            Operation::Substitution { expected: 'c', got: 'h' },
+            Operation::Match('k'), Operation::Match('l'), Operation::Match('y'),
+        ]);
+    }
+
+    #[test]
+    fn to_diff_string_test() {
+        let distribution = alphabet_distribution();
+        let last = OptimalAlignments::new("quickly", "qucehkly", &distribution).unwrap()
+            .alignment();
+
+        let diff = last.to_diff_string();
+        assert!(diff.contains("{+e}"));
+        assert!(diff.contains("{+h}"));
+        assert!(diff.contains("{-i}"));
+        assert_eq!(diff, "qu{-i}c{+e}{+h}kly");
+
+        let options = AlignmentOptions { selection: Selection::PreferDiagonal, ..AlignmentOptions::default() };
+        let diagonal = OptimalAlignments::with_options("quickly", "qucehkly", &distribution, &options).unwrap()
+            .alignment();
+        assert_eq!(diagonal.to_diff_string(), "qu{+c}[i/e][c/h]kly");
+
+        // markup characters in the texts are escaped
+        let distribution = Distribution::from_text("a[]{}/\\");
+        let escaped = OptimalAlignments::new("a[{", "a]/\\", &distribution).unwrap()
+            .alignment();
+        assert_eq!(escaped.to_diff_string(), r"a[\[/\]][\{/\/]{+\\}");
+        let escaped = OptimalAlignments::new("}a", "a", &distribution).unwrap()
+            .alignment();
+        assert_eq!(escaped.to_diff_string(), r"{-\}}a");
+    }
+
+    #[test]
+    fn perfect_transcription_test() {
+        let distribution = alphabet_distribution();
+        let alignments = OptimalAlignments::new("the quick ", "the quick ", &distribution).unwrap();
+
+        assert_eq!(alignments.n_correct(), 10);
+        assert_eq!(alignments.ixy(), Some(distribution.hx()));
+        // the general computation agrees
+        assert!(alignments.hyx().unwrap().abs() < 0.00000000001);
+
+        // undefined without any character, rather than a perfect transcription
+        let empty = OptimalAlignments::new("", "", &distribution).unwrap();
+        assert_eq!(empty.ixy(), None);
+    }
+
+    #[test]
+    fn with_precomputed_msd_test() {
+        let distribution = alphabet_distribution();
+
+        for &(presented, transcribed) in [("quickly", "qucehkly"), ("abcd", "acbd"), ("the fox", "the fox"), ("", "a")].iter() {
+            for selection in [Selection::Last, Selection::PreferDiagonal].iter() {
+                let options = AlignmentOptions { selection: *selection, gap_cost: 2, ..AlignmentOptions::default() };
+                let d = OptimalAlignments::msd_matrix(presented, transcribed, &options);
+                assert_eq!(
+                    OptimalAlignments::with_precomputed_msd(presented, transcribed, &distribution, &options, &d),
+                    OptimalAlignments::with_options(presented, transcribed, &distribution, &options),
+                );
+            }
+        }
+
+        let d = OptimalAlignments::msd_matrix("quickly", "qucehkly", &AlignmentOptions::default());
+        assert_eq!(d[7][8], 3);
+        assert_eq!(
+            OptimalAlignments::with_precomputed_msd("quickly", "qucehkly", &distribution, &AlignmentOptions::default(), &d),
+            OptimalAlignments::new("quickly", "qucehkly", &distribution),
+        );
+    }
+
+    #[test]
+    fn substitution_model_test() {
+        let mut frequencies = crate::Frequencies::new();
+        frequencies.record_str("aaabbc");
+        frequencies.entry_char('z');
+        let distribution = Distribution::new(frequencies);
+        assert_eq!(distribution.get('z'), Some(0.0));
+
+        let (a, b, z) = (Element::Character('a'), Element::Character('b'), Element::Character('z'));
+        let uniform = OptimalAlignments::new("abcab", "abbab", &distribution).unwrap();
+        let p_s = uniform.substitution_probability();
+        assert!(p_s > 0.0);
+        assert_eq!(uniform.channel().p_i_j(&a, &z), p_s / 3.0);
+
+        let options = AlignmentOptions { substitution_model: SubstitutionModel::Proportional, ..AlignmentOptions::default() };
+        let proportional = OptimalAlignments::with_options("abcab", "abbab", &distribution, &options).unwrap();
+        let channel = proportional.channel();
+        assert_eq!(channel.p_i_j(&a, &z), 0.0);
+        assert!(channel.p_i_j(&a, &b) > channel.p_i_j(&a, &Element::Character('c')));
+
+        // substitutions of a sum up to p(S)
+        let substituted = ['b', 'c', 'z'].iter()
+            .map(|&c| channel.p_i_j(&a, &Element::Character(c)))
+            .sum::<f64>();
+        assert!((substituted - p_s).abs() < 0.00000000001);
+        assert!(proportional.ixy().unwrap().is_finite());
+    }
+
+    #[test]
+    fn single_symbol_test() {
+        let distribution = Distribution::from_text("aaaa");
+        assert_eq!(distribution.hx(), 0.0);
+
+        for &(presented, transcribed) in [("aaa", "aa"), ("aa", "aaa"), ("aa", "aa"), ("a", "")].iter() {
+            let alignments = OptimalAlignments::new(presented, transcribed, &distribution).unwrap();
+            let channel = alignments.channel();
+            let a = Element::Character('a');
+            for (i, j) in [(&a, &a), (&Element::Null, &a), (&a, &Element::Null)].iter() {
+                assert!(channel.p_i_j(i, j).is_finite());
+            }
+            let ixy = alignments.ixy().unwrap();
+            assert!(!ixy.is_nan(), "{:?} {:?}", presented, transcribed);
+        }
+
+        // a substitution of another character is not expected
+        let alignments = OptimalAlignments::new("a", "a", &distribution).unwrap();
+        assert_eq!(alignments.channel().p_i_j(&Element::Character('a'), &Element::Character('b')), 0.0);
+    }
+
+    #[test]
+    fn costs_test() {
+        let distribution = alphabet_distribution();
+
+        let unit = OptimalAlignments::new("ab", "ba", &distribution).unwrap();
+        assert_eq!(unit, OptimalAlignments::with_costs("ab", "ba", &distribution, 1, 1).unwrap());
+        assert_eq!((unit.n_insertions(), unit.n_omissions(), unit.n_substitutions()), (1, 1, 0));
+
+        // an insertion and an omission cost more than a substitution
+        let gapped = OptimalAlignments::with_costs("ab", "ba", &distribution, 1, 2).unwrap();
+        assert_eq!((gapped.n_insertions(), gapped.n_omissions(), gapped.n_substitutions()), (0, 0, 2));
+
+        let d = msd_with_costs(&['a', 'b'], &['b', 'a'], Costs { substitution: 1, gap: 2 });
+        assert_eq!(d[2][2], 2);
+        assert_eq!(d[2][0], 4);
+    }
+
     #[test]
     fn alignment_test() {
         let presented = "quickly";
         let transcribed = "qucehkly";
 
         let distribution = alphabet_distribution();
-        let optimal_alignment = OptimalAlignments::new(presented, transcribed, &distribution);
+        let optimal_alignment = OptimalAlignments::new(presented, transcribed, &distribution).unwrap();
         let answer = OptimalAlignments {
             distribution: &distribution,
             presented: vec![
@@ -398,16 +1732,48 @@ mod test {
             ],
             p_null: 0.2222222222222222,
             len: 9,
+            min_probability: 0.0,
+            rate_normalization: RateNormalization::AlignedLength,
+            substitution_model: SubstitutionModel::Uniform,
         };
 
         assert_eq!(optimal_alignment, answer);
     }
 
+    #[test]
+    fn prefer_diagonal_test() {
+        let distribution = alphabet_distribution();
+        let options = AlignmentOptions { selection: Selection::PreferDiagonal, ..Default::default() };
+
+        // "ic" -> "ceh" is either an omission and two insertions, or two substitutions and an insertion
+        let last = OptimalAlignments::new("quickly", "qucehkly", &distribution).unwrap();
+        let diagonal = OptimalAlignments::with_options("quickly", "qucehkly", &distribution, &options).unwrap();
+
+        assert_eq!((last.n_omissions(), last.n_insertions(), last.n_substitutions()), (1, 2, 0));
+        assert_eq!((diagonal.n_omissions(), diagonal.n_insertions(), diagonal.n_substitutions()), (0, 1, 2));
+        assert_eq!(
+            diagonal.presented,
+            vec![
+                Element::Character('q'),
+                Element::Character('u'),
+                Element::Null,
+                Element::Character('i'),
+                Element::Character('c'),
+                Element::Character('k'),
+                Element::Character('l'),
+                Element::Character('y'),
+            ]
+        );
+
+        // deterministic
+        assert_eq!(diagonal, OptimalAlignments::with_options("quickly", "qucehkly", &distribution, &options).unwrap());
+    }
+
     fn sample_alignments<'a>(distribution: &'a Distribution) -> OptimalAlignments<'a> {
         let presented = "my watch fell in the waterprevailing wind from the east";
         let transcribed = "my wacch fell in waterpreviling wind on the east";
 
-        OptimalAlignments::new(presented, transcribed, distribution)
+        OptimalAlignments::new(presented, transcribed, distribution).unwrap()
     }
 
     #[test]
@@ -416,9 +1782,121 @@ mod test {
         let alignments = sample_alignments(&distribution);
 
         assert_eq!(alignments.insertion_probability(), 0.0);
-        assert_eq!(alignments.omission_probability(), 0.12727272727272726);
-        assert_eq!(alignments.substitution_probability(), 0.03636363636363636);
-        assert_eq!(alignments.probability_of_correct_entries(), 0.8363636363636363);
+        assert!(approx_eq(alignments.omission_probability(), 7.0 / 55.0, 1e-12));
+        assert!(approx_eq(alignments.substitution_probability(), 2.0 / 55.0, 1e-12));
+        assert!(approx_eq(alignments.probability_of_correct_entries(), 46.0 / 55.0, 1e-12));
+    }
+
+    #[test]
+    fn rate_normalization_test() {
+        let distribution = alphabet_distribution();
+        let (presented, transcribed) = (
+            "my watch fell in the waterprevailing wind from the east",
+            "my wacch fell in waterpreviling wind on the east",
+        );
+
+        // 2 insertions, 1 omission and 6 correct entries of 7 presented characters in 9 aligned positions
+        let options = AlignmentOptions { rate_normalization: RateNormalization::PresentedLength, ..AlignmentOptions::default() };
+        let default = OptimalAlignments::new("quickly", "qucehkly", &distribution).unwrap();
+        let alignments = OptimalAlignments::with_options("quickly", "qucehkly", &distribution, &options).unwrap();
+        assert!(approx_eq(default.insertion_probability(), 2.0 / 9.0, 1e-12));
+        assert!(approx_eq(default.omission_probability(), 1.0 / 9.0, 1e-12));
+        assert!(approx_eq(alignments.insertion_probability(), 2.0 / 7.0, 1e-12));
+        assert!(approx_eq(alignments.omission_probability(), 1.0 / 7.0, 1e-12));
+        assert!(approx_eq(alignments.probability_of_correct_entries(), 6.0 / 7.0, 1e-12));
+        assert_eq!(alignments.error_rates().insertion, alignments.insertion_probability());
+
+        // the channel is the paper's regardless of the normalization
+        assert_eq!(alignments.channel().rates, default.error_rates());
+        assert_eq!(alignments.ixy(), default.ixy());
+
+        // 48 transcribed characters
+        let options = AlignmentOptions { rate_normalization: RateNormalization::TranscribedLength, ..AlignmentOptions::default() };
+        let alignments = OptimalAlignments::with_options(presented, transcribed, &distribution, &options).unwrap();
+        assert_eq!(alignments.insertion_probability(), 0.0);
+        assert!(approx_eq(alignments.omission_probability(), 7.0 / 48.0, 1e-12));
+        assert!(approx_eq(alignments.substitution_probability(), 2.0 / 48.0, 1e-12));
+        assert!(approx_eq(alignments.probability_of_correct_entries(), 46.0 / 48.0, 1e-12));
+        assert_eq!(alignments.ixy(), sample_alignments(&distribution).ixy());
+    }
+
+    #[test]
+    fn counts_test() {
+        let distribution = alphabet_distribution();
+        let alignments = sample_alignments(&distribution);
+
+        assert_eq!(alignments.p_null(), 0.0);
+        assert_eq!(alignments.aligned_length(), 55);
+        assert_eq!(alignments.n_insertions(), 0);
+        // 0.12727272727272726 = 7 / 55
+        assert_eq!(alignments.n_omissions(), 7);
+        // 0.03636363636363636 = 2 / 55
+        assert_eq!(alignments.n_substitutions(), 2);
+        // 0.8363636363636363 = 46 / 55
+        assert_eq!(alignments.n_correct(), 46);
+    }
+
+    #[test]
+    fn null_estimator_test() {
+        let distribution = alphabet_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let default = OptimalAlignments::new(presented, transcribed, &distribution).unwrap();
+        let fixed = OptimalAlignments::with_options(
+            presented, transcribed, &distribution,
+            &AlignmentOptions { null_estimator: NullEstimator::Fixed(0.0), ..Default::default() },
+        ).unwrap();
+
+        assert_eq!(default.p_null, 0.0);
+        assert_eq!(fixed.p_null, default.p_null);
+        assert_eq!(fixed.ixy(), default.ixy());
+
+        // 2 insertions and 1 omission in 9 aligned positions
+        let (presented, transcribed) = ("quickly", "qucehkly");
+        let default = OptimalAlignments::new(presented, transcribed, &distribution).unwrap();
+        let all_positions = OptimalAlignments::with_options(
+            presented, transcribed, &distribution,
+            &AlignmentOptions { null_estimator: NullEstimator::AllPositions, ..Default::default() },
+        ).unwrap();
+
+        assert_eq!(default.p_null, 2.0 / 9.0);
+        assert_eq!(all_positions.p_null, 3.0 / 18.0);
+    }
+
+    #[test]
+    fn min_probability_test() {
+        let mut distribution = alphabet_distribution();
+        distribution.map.insert('q', 1e-300);
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+
+        let ixy = OptimalAlignments::new(presented, transcribed, &distribution).unwrap()
+            .ixy().unwrap();
+        let floored = OptimalAlignments::new(presented, transcribed, &distribution).unwrap()
+            .min_probability(1e-12)
+            .ixy().unwrap();
+
+        assert!(floored.is_finite());
+        assert!((floored - ixy).abs() < 0.000001);
+    }
+
+    #[test]
+    fn input_too_long_test() {
+        let distribution = alphabet_distribution();
+
+        // a matrix of 10^10 cells would not fit in memory
+        let long = "a".repeat(100_000);
+        let result = OptimalAlignments::new(&long, &long, &distribution);
+        assert_eq!(result, Err(TetError::InputTooLong { limit: 10_000_000 }));
+
+        let options = AlignmentOptions { max_cells: 20, ..AlignmentOptions::default() };
+        assert!(OptimalAlignments::with_options("abcd", "abcde", &distribution, &options).is_ok());
+        assert_eq!(
+            OptimalAlignments::with_options("abcde", "abcde", &distribution, &options),
+            Err(TetError::InputTooLong { limit: 20 }),
+        );
     }
 
     fn alphabet_distribution() -> Distribution {
@@ -446,28 +1924,54 @@ mod test {
         Distribution { map }
     }
 
+    #[test]
+    fn per_position_information_test() {
+        let distribution = alphabet_distribution();
+        let alignments = sample_alignments(&distribution);
+
+        let information = alignments.per_position_information().unwrap();
+        assert_eq!(information.len(), alignments.aligned_length());
+
+        // omissions carry no information
+        alignments.transcribed.iter()
+            .zip(information.iter())
+            .filter(|(j, _)| j.is_null())
+            .for_each(|(_, &bits)| assert!(bits.abs() < 0.00000000001));
+
+        let total = alignments.ixy().unwrap() * alignments.aligned_length() as f64;
+        let sum = information.iter().sum::<f64>();
+        assert!((sum - total).abs() / total < 0.02);
+    }
+
     #[test]
     fn ixy_test() {
         let distribution = alphabet_distribution();
 
         let alignments = sample_alignments(&distribution);
 
-        // insertion probability
-        assert_eq!(alignments.insertion_probability(), 0.0);
-        // omission probability
-        assert_eq!(alignments.omission_probability(), 0.12727272727272726);
-        // substitution probability
-        assert_eq!(alignments.substitution_probability(), 0.03636363636363636);
-        // correct probability
-        assert_eq!(alignments.probability_of_correct_entries(), 0.8363636363636363);
+        let expected = Intermediates {
+            hx: 4.090309047790043,
+            hyx: 0.8515677144377292,
+            ixy: 3.238741333352314,
+            p_null: 0.0,
+            insertion_probability: 0.0,
+            omission_probability: 0.12727272727272726,
+            substitution_probability: 0.03636363636363636,
+            probability_of_correct_entries: 0.8363636363636363,
+        };
+        let intermediates = alignments.intermediates().unwrap();
+        assert!(intermediates.approx_eq(&expected, 1e-9));
 
         // H(X)
-        assert!((distribution.hx() - 4.090309047790043).abs() < 0.00000000001);
+        assert!(approx_eq(distribution.hx(), 4.090309047790043, 0.00000000001));
 
         // H_Y(X)
-        assert!(alignments.hyx().unwrap() - 0.8515677144377292 < 0.00000000001);
+        assert!(approx_eq(alignments.hyx().unwrap(), 0.8515677144377292, 0.00000000001));
 
         // I(X,Y): bits/character
-        assert!(alignments.ixy().unwrap() - 3.238741333352314 < 0.00000000001);
+        assert!(approx_eq(alignments.ixy().unwrap(), 3.238741333352314, 0.00000000001));
+
+        let off = Intermediates { ixy: expected.ixy + 2e-9, ..expected };
+        assert!(!intermediates.approx_eq(&off, 1e-9));
     }
 }
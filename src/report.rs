@@ -0,0 +1,107 @@
+use crate::optimal_alignments::Alignment;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// metrics of a trial derived from one alignment pass
+///
+/// See [`TextEntryThroughput::analyze`](crate::TextEntryThroughput::analyze).
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TetReport {
+    /// text entry throughput (bits/s)
+    pub throughput: f64,
+    /// minimum text entry throughput (bits/s) over evaluated alignments
+    pub throughput_min: f64,
+    /// maximum text entry throughput (bits/s) over evaluated alignments
+    pub throughput_max: f64,
+    /// characters entered per second (see [`TimeBasis`](crate::TimeBasis))
+    pub chars_per_second: f64,
+    /// I(X,Y): information transmitted per character (bits/character)
+    pub bits_per_character: f64,
+    /// character error rate: minimum string distance over the number of presented characters
+    pub cer: f64,
+    /// minimum string distance
+    pub msd: u32,
+    /// p(I)
+    pub insertion_probability: f64,
+    /// p(M)
+    pub omission_probability: f64,
+    /// p(S)
+    pub substitution_probability: f64,
+    /// p(C)
+    pub probability_of_correct_entries: f64,
+    /// whether averaging over optimal alignments was given up for too many alignments
+    ///
+    /// See [`TextEntryThroughput::max_alignments`](crate::TextEntryThroughput::max_alignments).
+    pub truncated_alignments: bool,
+    /// control characters sanitized from texts
+    ///
+    /// See [`TextEntryThroughput::sanitize`](crate::TextEntryThroughput::sanitize).
+    pub sanitized_chars: Vec<char>,
+    /// evaluated alignments: every optimal alignment when averaging, otherwise the selected one
+    pub alignments: Vec<Alignment>,
+}
+
+#[cfg(feature = "serde1")]
+impl TetReport {
+    /// serialize the report into a JSON object
+    ///
+    /// Floats are written with the shortest representation that parses back to the same value.
+    /// In alignments, characters are written as strings and NULL as `null`.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("report has only numbers and characters")
+    }
+
+    /// deserialize a report from a JSON object written by [`to_json`](TetReport::to_json)
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(all(test, feature = "serde1"))]
+mod test {
+    use super::*;
+    use crate::TextEntryThroughput;
+
+    #[test]
+    fn to_json_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let report = tet.analyze(presented, transcribed, s).unwrap();
+        let json = report.to_json();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["throughput"].as_f64(), Some(report.throughput));
+        assert!((report.throughput - 12.954965333409255).abs() < 0.0001);
+        assert_eq!(value["msd"].as_u64(), Some(report.msd as u64));
+        assert_eq!(value["alignments"][0]["presented"][0], "m");
+        assert_eq!(value["alignments"][0]["transcribed"][20], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = std::time::Duration::from_secs(12);
+
+        let report = tet.analyze(presented, transcribed, s).unwrap();
+        let deserialized = TetReport::from_json(&report.to_json()).unwrap();
+
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+        assert!(close(deserialized.throughput, report.throughput));
+        assert!(close(deserialized.bits_per_character, report.bits_per_character));
+        assert!(close(deserialized.cer, report.cer));
+        assert!(close(deserialized.insertion_probability, report.insertion_probability));
+        assert!(close(deserialized.omission_probability, report.omission_probability));
+        assert!(close(deserialized.substitution_probability, report.substitution_probability));
+        assert!(close(deserialized.probability_of_correct_entries, report.probability_of_correct_entries));
+        assert_eq!(deserialized.msd, report.msd);
+        assert_eq!(deserialized.alignments, report.alignments);
+    }
+}
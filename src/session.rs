@@ -0,0 +1,571 @@
+use crate::statistics::{mean, variance};
+use crate::{studies, ConfusionMatrix, ErrorRates, Result, TetError, TextEntryThroughput};
+#[cfg(feature = "sim")]
+use rand::Rng;
+#[cfg(feature = "serde1")]
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// a trial recorded in a session
+#[derive(Debug, Clone, PartialEq)]
+struct Trial {
+    /// information transmitted in the trial (bits)
+    bits: f64,
+    /// time in seconds required for the trial
+    seconds: f64,
+    /// number of transcribed characters
+    chars: usize,
+    /// presented text
+    presented: String,
+    /// transcribed text
+    transcribed: String,
+}
+
+/// summary of a session
+///
+/// See [`Session::summary`](Session::summary).
+#[cfg_attr(feature = "serde1", derive(Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSummary {
+    /// text entry throughput (bits/s) of each trial, in the order of recording
+    pub throughputs: Vec<f64>,
+    /// pooled text entry throughput (bits/s)
+    pub pooled_throughput: Option<f64>,
+    /// mean of throughputs weighted by transcribed characters (bits/s)
+    pub weighted_mean_throughput: Option<f64>,
+    /// unweighted mean of throughputs (bits/s)
+    pub mean_throughput: Option<f64>,
+    /// sample standard deviation of throughputs (bits/s), for two or more trials
+    pub sd_throughput: Option<f64>,
+    /// number of recorded trials
+    pub trial_count: usize,
+    /// rates of edit operations over aligned positions of all trials
+    pub error_rates: Option<ErrorRates>,
+}
+
+/// a series of trials computed with the same distribution
+///
+/// Throughput of a session is pooled: total bits transmitted over total time.
+pub struct Session<'a> {
+    tet: &'a TextEntryThroughput,
+    trials: Vec<Trial>,
+    /// total bits of recorded trials
+    bits: f64,
+    /// total seconds of recorded trials
+    seconds: f64,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(tet: &'a TextEntryThroughput) -> Self {
+        Self {
+            tet,
+            trials: Vec::new(),
+            bits: 0.0,
+            seconds: 0.0,
+        }
+    }
+
+    /// record a trial and return its text entry throughput (bits/s)
+    ///
+    /// A failing trial, e.g. of no time, is not recorded and `None` is returned.
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    /// - s: time in seconds required for entry transcribed text
+    pub fn record(&mut self, presented: &str, transcribed: &str, s: std::time::Duration) -> Option<f64> {
+        if s.is_zero() {
+            return None;
+        }
+        let bits = self.tet.bits_transmitted(presented, transcribed).ok()?;
+        let seconds = s.as_secs_f64();
+        let chars = transcribed.chars().count();
+
+        self.push(Trial {
+            bits,
+            seconds,
+            chars,
+            presented: presented.to_string(),
+            transcribed: transcribed.to_string(),
+        });
+
+        Some(bits / seconds)
+    }
+
+    /// record a trial and return the updated pooled throughput (bits/s)
+    ///
+    /// Only totals are updated, so prior trials are not recomputed.
+    /// A failing trial, e.g. of no time ([`TetError::ZeroDuration`](TetError::ZeroDuration)), is not recorded.
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    /// - s: time in seconds required for entry transcribed text
+    pub fn push_trial(&mut self, presented: &str, transcribed: &str, s: std::time::Duration) -> Result<f64> {
+        if s.is_zero() {
+            return Err(TetError::ZeroDuration);
+        }
+        let bits = self.tet.bits_transmitted(presented, transcribed)?;
+        let seconds = s.as_secs_f64();
+        let chars = transcribed.chars().count();
+
+        self.push(Trial {
+            bits,
+            seconds,
+            chars,
+            presented: presented.to_string(),
+            transcribed: transcribed.to_string(),
+        });
+
+        self.throughput().ok_or(TetError::UndefinedInformation)
+    }
+
+    fn push(&mut self, trial: Trial) {
+        self.bits += trial.bits;
+        self.seconds += trial.seconds;
+        self.trials.push(trial);
+    }
+
+    /// pooled text entry throughput (bits/s) of recorded trials
+    pub fn throughput(&self) -> Option<f64> {
+        if self.seconds > 0.0 {
+            Some(self.bits / self.seconds)
+        } else {
+            None
+        }
+    }
+
+    /// number of recorded trials
+    ///
+    /// Failing trials are not recorded.
+    pub fn trial_count(&self) -> usize {
+        self.trials.len()
+    }
+
+    /// pooled text entry throughput (bits/s), if at least `min_trials` trials are recorded
+    pub fn throughput_if_enough(&self, min_trials: usize) -> Option<f64> {
+        if self.trial_count() < min_trials {
+            return None;
+        }
+
+        self.throughput()
+    }
+
+    /// mean of per-trial throughputs (bits/s) weighted by transcribed characters
+    ///
+    /// Unlike the unweighted mean, a long trial counts more than a short one.
+    /// Unlike the pooled `throughput`, each trial is weighted by its length
+    /// rather than by its time, so slow trials do not dominate.
+    /// `NaN` if no characters were transcribed.
+    pub fn weighted_mean_throughput(&self) -> f64 {
+        let (sum, chars) = self.trials.iter()
+            .fold((0.0, 0), |(sum, chars), trial| {
+                (sum + trial.chars as f64 * trial.bits / trial.seconds, chars + trial.chars)
+            });
+
+        sum / chars as f64
+    }
+
+    /// confusion matrix of recorded trials
+    ///
+    /// Trials are aligned again in the same way as they were recorded.
+    pub fn confusion_matrix(&self) -> Result<ConfusionMatrix> {
+        let mut matrix = ConfusionMatrix::new();
+
+        for trial in self.trials.iter() {
+            let alignments = self.tet.alignments(&trial.presented, &trial.transcribed)?;
+            matrix.record_alignment(&alignments.alignment());
+        }
+
+        Ok(matrix)
+    }
+
+    /// summary of recorded trials
+    ///
+    /// Trials are aligned again for error rates in the same way as they were recorded.
+    /// Statistics are `None` for an empty session.
+    pub fn summary(&self) -> SessionSummary {
+        let mut throughputs = Vec::with_capacity(self.trials.len());
+        let (mut weighted, mut chars) = (0.0, 0);
+        // insertions, omissions, substitutions, correct entries
+        let mut counts = [0usize; 4];
+
+        for trial in self.trials.iter() {
+            let throughput = trial.bits / trial.seconds;
+            throughputs.push(throughput);
+            weighted += trial.chars as f64 * throughput;
+            chars += trial.chars;
+
+            if let Ok(alignments) = self.tet.alignments(&trial.presented, &trial.transcribed) {
+                counts[0] += alignments.n_insertions();
+                counts[1] += alignments.n_omissions();
+                counts[2] += alignments.n_substitutions();
+                counts[3] += alignments.n_correct();
+            }
+        }
+
+        let aligned_length = counts.iter().sum::<usize>() as f64;
+        let error_rates = if aligned_length > 0.0 {
+            Some(ErrorRates {
+                insertion: counts[0] as f64 / aligned_length,
+                omission: counts[1] as f64 / aligned_length,
+                substitution: counts[2] as f64 / aligned_length,
+                correct: counts[3] as f64 / aligned_length,
+            })
+        } else {
+            None
+        };
+
+        SessionSummary {
+            pooled_throughput: self.throughput(),
+            weighted_mean_throughput: if chars > 0 { Some(weighted / chars as f64) } else { None },
+            mean_throughput: if throughputs.is_empty() { None } else { Some(mean(&throughputs)) },
+            sd_throughput: if throughputs.len() < 2 { None } else { Some(variance(&throughputs).sqrt()) },
+            trial_count: throughputs.len(),
+            throughputs,
+            error_rates,
+        }
+    }
+
+    #[cfg(feature = "sim")]
+    fn pooled<'t, I: Iterator<Item=&'t Trial>>(trials: I) -> Option<f64> {
+        let (bits, seconds) = trials
+            .fold((0.0, 0.0), |(bits, seconds), trial| {
+                (bits + trial.bits, seconds + trial.seconds)
+            });
+
+        if seconds > 0.0 {
+            Some(bits / seconds)
+        } else {
+            None
+        }
+    }
+
+    /// bootstrap confidence interval of the pooled throughput (bits/s)
+    ///
+    /// Trials are resampled with replacement `iterations` times and
+    /// the empirical `alpha / 2` and `1 - alpha / 2` percentiles are returned,
+    /// e.g. `alpha = 0.05` for a 95% interval.
+    /// Both bounds are `NaN` for an empty session.
+    #[cfg(feature = "sim")]
+    pub fn bootstrap_ci(&self, iterations: usize, alpha: f64, rng: &mut impl Rng) -> (f64, f64) {
+        if self.trials.is_empty() || iterations == 0 {
+            return (f64::NAN, f64::NAN);
+        }
+
+        let mut samples = (0..iterations)
+            .map(|_| {
+                let resampled = (0..self.trials.len())
+                    .map(|_| &self.trials[rng.gen_range(0..self.trials.len())]);
+
+                Self::pooled(resampled).unwrap_or(f64::NAN)
+            })
+            .collect::<Vec<_>>();
+        samples.sort_by(|a, b| a.total_cmp(b));
+
+        let percentile = |q: f64| {
+            let index = (q * (iterations - 1) as f64).round() as usize;
+            samples[index.min(iterations - 1)]
+        };
+
+        (percentile(alpha / 2.0), percentile(1.0 - alpha / 2.0))
+    }
+}
+
+/// trials of participants computed with the same distribution
+///
+/// Throughput of each participant is pooled as in [`Session`](Session).
+pub struct GroupedSession<'a> {
+    tet: &'a TextEntryThroughput,
+    trials: HashMap<String, Vec<studies::Trial>>,
+}
+
+impl<'a> GroupedSession<'a> {
+    pub fn new(tet: &'a TextEntryThroughput) -> Self {
+        Self {
+            tet,
+            trials: HashMap::new(),
+        }
+    }
+
+    /// record a trial of the participant
+    pub fn push(&mut self, participant: &str, trial: studies::Trial) {
+        self.trials.entry(participant.to_string())
+            .or_default()
+            .push(trial);
+    }
+
+    /// pooled throughput (bits/s) of trials of a participant
+    fn pooled(&self, trials: &[studies::Trial]) -> Option<f64> {
+        let mut session = Session::new(self.tet);
+        for trial in trials {
+            session.record(&trial.presented, &trial.transcribed, trial.duration);
+        }
+
+        session.throughput()
+    }
+
+    /// pooled text entry throughput (bits/s) of each participant
+    ///
+    /// Failing trials, e.g. of no time, are skipped as in [`Session::record`](Session::record),
+    /// and a participant without successful trials is left out.
+    /// With `rayon` feature, participants are computed in parallel.
+    pub fn per_participant_throughput(&self) -> HashMap<String, f64> {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+
+            self.trials.par_iter()
+                .filter_map(|(participant, trials)| Some((participant.clone(), self.pooled(trials)?)))
+                .collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            self.trials.iter()
+                .filter_map(|(participant, trials)| Some((participant.clone(), self.pooled(trials)?)))
+                .collect()
+        }
+    }
+
+    /// mean and standard deviation of per-participant throughputs (bits/s)
+    ///
+    /// The deviation is of the unbiased sample variance, so it is `NaN` for fewer than two participants.
+    /// Both are `NaN` without participants.
+    pub fn group_mean_sd(&self) -> (f64, f64) {
+        let mut throughputs = self.per_participant_throughput()
+            .into_iter()
+            .collect::<Vec<_>>();
+        // sum in the order of participants so that the result does not depend on the order of the map
+        throughputs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let throughputs = throughputs.into_iter()
+            .map(|(_, throughput)| throughput)
+            .collect::<Vec<_>>();
+
+        if throughputs.len() < 2 {
+            return (mean(&throughputs), f64::NAN);
+        }
+
+        (mean(&throughputs), variance(&throughputs).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[cfg(feature = "sim")]
+    fn sample_session(tet: &TextEntryThroughput) -> Session<'_> {
+        let mut session = Session::new(tet);
+
+        let trials = [
+            ("my watch fell in the water", "my wacch fell in te water", 7),
+            ("prevailing wind from the east", "previling wind on the east", 9),
+            ("the quick brown fox", "the quik brwn fix", 4),
+            ("never too rich and never too thin", "nevr too rich and nevet to thin", 12),
+            ("time to go shopping", "tme to go shoppimg", 5),
+        ];
+
+        for (presented, transcribed, s) in trials.iter() {
+            session.record(presented, transcribed, Duration::from_secs(*s)).unwrap();
+        }
+
+        session
+    }
+
+    #[test]
+    fn throughput_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let mut session = Session::new(&tet);
+        assert_eq!(session.throughput(), None);
+
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let s = Duration::from_secs(12);
+
+        let single = session.record(presented, transcribed, s).unwrap();
+        session.record(presented, transcribed, s).unwrap();
+
+        assert!((session.throughput().unwrap() - single).abs() < 0.0000001);
+
+        // a trial of no time is not recorded
+        assert_eq!(session.record(presented, transcribed, Duration::ZERO), None);
+        assert_eq!(session.trial_count(), 2);
+        assert!((session.throughput().unwrap() - single).abs() < 0.0000001);
+        assert!(session.summary().throughputs.iter().all(|throughput| throughput.is_finite()));
+    }
+
+    #[test]
+    fn push_trial_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution().max_cells(1000);
+        let mut session = Session::new(&tet);
+
+        // a trial of no time is not recorded
+        assert_eq!(
+            session.push_trial("the quick", "the quick", Duration::ZERO),
+            Err(TetError::ZeroDuration),
+        );
+        assert_eq!(session.trial_count(), 0);
+        assert_eq!(session.throughput(), None);
+
+        let trials = [
+            ("my watch fell in the water", "my wacch fell in te water", 7),
+            ("prevailing wind from the east", "previling wind on the east", 9),
+            ("the quick brown fox", "the quik brwn fix", 4),
+        ];
+
+        let (mut bits, mut seconds) = (0.0, 0.0);
+        for &(presented, transcribed, s) in trials.iter() {
+            let running = session.push_trial(presented, transcribed, Duration::from_secs(s)).unwrap();
+
+            bits += tet.bits_transmitted(presented, transcribed).unwrap();
+            seconds += s as f64;
+            assert!((running - bits / seconds).abs() < 0.00000000001);
+            assert_eq!(session.throughput(), Some(running));
+        }
+
+        // a failing trial leaves totals as they were
+        let long = "a".repeat(100);
+        assert_eq!(
+            session.push_trial(&long, &long, Duration::from_secs(1)),
+            Err(TetError::InputTooLong { limit: 1000 }),
+        );
+        assert!((session.throughput().unwrap() - bits / seconds).abs() < 0.00000000001);
+    }
+
+    #[test]
+    fn throughput_if_enough_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let mut session = Session::new(&tet);
+        session.record("the fox", "the fix", Duration::from_secs(3)).unwrap();
+        session.record("a box", "a bix", Duration::from_secs(2)).unwrap();
+        assert!(session.record("a box", "a b1x", Duration::from_secs(2)).is_none());
+
+        assert_eq!(session.trial_count(), 2);
+        assert_eq!(session.throughput_if_enough(3), None);
+        assert_eq!(session.throughput_if_enough(2), session.throughput());
+        assert!(session.throughput_if_enough(2).is_some());
+    }
+
+    #[test]
+    fn weighted_mean_throughput_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let mut session = Session::new(&tet);
+        assert!(session.weighted_mean_throughput().is_nan());
+
+        let short = session.record("the fox", "the fix", Duration::from_secs(3)).unwrap();
+        let presented = "my watch fell in the waterprevailing wind from the east";
+        let transcribed = "my wacch fell in waterpreviling wind on the east";
+        let long = session.record(presented, transcribed, Duration::from_secs(12)).unwrap();
+
+        let weighted = session.weighted_mean_throughput();
+        let (lower, upper) = (short.min(long), short.max(long));
+        assert!(lower < weighted && weighted < upper);
+        assert!((weighted - long).abs() < (weighted - short).abs());
+    }
+
+    #[test]
+    fn summary_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let mut session = Session::new(&tet);
+
+        let empty = session.summary();
+        assert_eq!(empty.trial_count, 0);
+        assert!(empty.throughputs.is_empty());
+        assert_eq!((empty.pooled_throughput, empty.mean_throughput, empty.sd_throughput), (None, None, None));
+        assert_eq!((empty.weighted_mean_throughput, empty.error_rates), (None, None));
+
+        let throughputs = vec![
+            session.record("the fox", "the fix", Duration::from_secs(3)).unwrap(),
+            session.record("a box", "a bx", Duration::from_secs(2)).unwrap(),
+            session.record("abc", "abxc", Duration::from_secs(1)).unwrap(),
+        ];
+
+        let summary = session.summary();
+        assert_eq!(summary.trial_count, 3);
+        assert_eq!(summary.throughputs, throughputs);
+        assert_eq!(summary.pooled_throughput, session.throughput());
+        assert!((summary.weighted_mean_throughput.unwrap() - session.weighted_mean_throughput()).abs() < 0.00000000001);
+
+        let mean = throughputs.iter().sum::<f64>() / 3.0;
+        let sd = (throughputs.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / 2.0).sqrt();
+        assert!((summary.mean_throughput.unwrap() - mean).abs() < 0.00000000001);
+        assert!((summary.sd_throughput.unwrap() - sd).abs() < 0.00000000001);
+
+        // a substitution, an omission and an insertion over 16 aligned positions
+        let rates = summary.error_rates.unwrap();
+        assert_eq!(rates.insertion, 1.0 / 16.0);
+        assert_eq!(rates.omission, 1.0 / 16.0);
+        assert_eq!(rates.substitution, 1.0 / 16.0);
+        assert_eq!(rates.correct, 13.0 / 16.0);
+    }
+
+    #[test]
+    fn confusion_matrix_test() {
+        use crate::Element;
+
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let mut session = Session::new(&tet);
+        session.record("the fox", "the fix", Duration::from_secs(3)).unwrap();
+        session.record("a box", "a bix", Duration::from_secs(2)).unwrap();
+        session.record("quickly", "qucehkly", Duration::from_secs(4)).unwrap();
+
+        let matrix = session.confusion_matrix().unwrap();
+        let c = Element::Character;
+
+        assert_eq!(matrix.get(c('o'), c('i')), 2);
+        assert_eq!(matrix.get(c('x'), c('x')), 2);
+        assert_eq!(matrix.get(c(' '), c(' ')), 2);
+        assert_eq!(matrix.get(c('i'), Element::Null), 1);
+        assert_eq!(matrix.get(Element::Null, c('e')), 1);
+        assert_eq!(matrix.get(c('i'), c('o')), 0);
+    }
+
+    #[test]
+    fn grouped_session_test() {
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let mut group = GroupedSession::new(&tet);
+        assert!(group.per_participant_throughput().is_empty());
+        assert!(group.group_mean_sd().0.is_nan());
+
+        let trial = |presented: &str, transcribed: &str, s| studies::Trial {
+            presented: presented.to_string(),
+            transcribed: transcribed.to_string(),
+            duration: Duration::from_secs(s),
+        };
+        group.push("p1", trial("the fox", "the fix", 3));
+        group.push("p2", trial("a box", "a bix", 2));
+        group.push("p1", trial("quickly", "qucehkly", 4));
+        // trials of no time are skipped
+        group.push("p2", trial("a fox", "a fix", 0));
+        group.push("p3", trial("a fox", "a fix", 0));
+
+        let p1 = (tet.bits_transmitted("the fox", "the fix").unwrap()
+            + tet.bits_transmitted("quickly", "qucehkly").unwrap()) / 7.0;
+        let p2 = tet.calc("a box", "a bix", Duration::from_secs(2)).unwrap();
+
+        let throughputs = group.per_participant_throughput();
+        assert_eq!(throughputs.len(), 2);
+        assert!(!throughputs.contains_key("p3"));
+        assert!((throughputs["p1"] - p1).abs() < 0.00000000001);
+        assert!((throughputs["p2"] - p2).abs() < 0.00000000001);
+
+        let (mean, sd) = group.group_mean_sd();
+        assert!((mean - (p1 + p2) / 2.0).abs() < 0.00000000001);
+        assert!((sd - (p1 - p2).abs() / 2f64.sqrt()).abs() < 0.00000000001);
+    }
+
+    #[cfg(feature = "sim")]
+    #[test]
+    fn bootstrap_ci_test() {
+        use rand::SeedableRng;
+
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let session = sample_session(&tet);
+        let mean = session.throughput().unwrap();
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(320);
+        let (lower, upper) = session.bootstrap_ci(1000, 0.05, &mut rng);
+
+        assert!(lower < mean);
+        assert!(mean < upper);
+    }
+}
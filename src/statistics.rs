@@ -0,0 +1,80 @@
+/// summary of a comparison between two samples of throughputs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// mean(a) - mean(b)
+    pub mean_difference: f64,
+    /// pooled standard deviation of a and b
+    pub pooled_sd: f64,
+    /// Welch's t-statistic
+    pub t: f64,
+    /// Welch–Satterthwaite degrees of freedom
+    pub df: f64,
+}
+
+/// compare two samples (e.g. throughputs of two input methods) with Welch's t-test
+///
+/// Each sample needs at least two values; otherwise fields are `NaN`.
+pub fn compare(a: &[f64], b: &[f64]) -> Comparison {
+    let (na, nb) = (a.len() as f64, b.len() as f64);
+    let (va, vb) = (variance(a), variance(b));
+
+    let mean_difference = mean(a) - mean(b);
+    let pooled_sd = (((na - 1.0) * va + (nb - 1.0) * vb) / (na + nb - 2.0)).sqrt();
+
+    let (sa, sb) = (va / na, vb / nb);
+    let t = mean_difference / (sa + sb).sqrt();
+    let df = (sa + sb).powi(2)
+        / (sa.powi(2) / (na - 1.0) + sb.powi(2) / (nb - 1.0));
+
+    Comparison { mean_difference, pooled_sd, t, df }
+}
+
+/// whether `a` and `b` differ by at most `tol` (never for `NaN`)
+pub(crate) fn approx_eq(a: f64, b: f64, tol: f64) -> bool {
+    (a - b).abs() <= tol
+}
+
+/// arithmetic mean
+pub(crate) fn mean(xs: &[f64]) -> f64 {
+    xs.iter().sum::<f64>() / xs.len() as f64
+}
+
+/// unbiased sample variance
+pub(crate) fn variance(xs: &[f64]) -> f64 {
+    let m = mean(xs);
+
+    xs.iter()
+        .map(|x| (x - m).powi(2))
+        .sum::<f64>()
+        / (xs.len() as f64 - 1.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn approx_eq_test() {
+        assert!(approx_eq(0.1 + 0.2, 0.3, 1e-9));
+        assert!(approx_eq(1.0, 1.0 + 0.5e-9, 1e-9));
+        assert!(!approx_eq(1.0, 1.0 + 2e-9, 1e-9));
+        assert!(!approx_eq(f64::NAN, f64::NAN, 1e-9));
+    }
+
+    #[test]
+    fn compare_test() {
+        let a = [10.0, 12.0, 14.0];
+        let b = [8.0, 9.0, 10.0];
+
+        let comparison = compare(&a, &b);
+
+        // var(a) = 4, var(b) = 1
+        assert!((comparison.mean_difference - 3.0).abs() < 0.00000000001);
+        // sqrt((2 * 4 + 2 * 1) / 4)
+        assert!((comparison.pooled_sd - 2.5f64.sqrt()).abs() < 0.00000000001);
+        // 3 / sqrt(4 / 3 + 1 / 3)
+        assert!((comparison.t - 2.32379000772445).abs() < 0.00000000001);
+        // (5 / 3)^2 / ((4 / 3)^2 / 2 + (1 / 3)^2 / 2)
+        assert!((comparison.df - 50.0 / 17.0).abs() < 0.00000000001);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::error::{Result, TetError};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// a trial of a study
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trial {
+    /// presented text
+    pub presented: String,
+    /// transcribed text
+    pub transcribed: String,
+    /// time required for entry transcribed text
+    pub duration: Duration,
+}
+
+/// load trials from a CSV log with columns `presented`, `transcribed` and `seconds`
+///
+/// The first row is a header; other columns are ignored.
+#[cfg(feature = "csv")]
+pub fn load_trials_csv<R: std::io::Read>(r: R) -> Result<Vec<Trial>> {
+    let mut reader = csv::Reader::from_reader(r);
+
+    let headers = reader.headers()
+        .map_err(|e| TetError::InvalidLog(e.to_string()))?
+        .clone();
+    let column = |name: &str| {
+        headers.iter()
+            .position(|header| header.trim() == name)
+            .ok_or_else(|| TetError::InvalidLog(format!("no column {:?}", name)))
+    };
+    let (presented, transcribed, seconds) = (column("presented")?, column("transcribed")?, column("seconds")?);
+
+    reader.records()
+        .map(|record| {
+            let record = record.map_err(|e| TetError::InvalidLog(e.to_string()))?;
+            let field = |i: usize| {
+                record.get(i)
+                    .ok_or_else(|| TetError::InvalidLog(format!("missing field in {:?}", record)))
+            };
+
+            let s = field(seconds)?;
+            let duration = s.trim().parse::<f64>().ok()
+                .filter(|s| s.is_finite() && *s >= 0.0)
+                .map(Duration::from_secs_f64)
+                .ok_or_else(|| TetError::InvalidLog(format!("invalid seconds {:?}", s)))?;
+
+            Ok(Trial {
+                presented: field(presented)?.to_string(),
+                transcribed: field(transcribed)?.to_string(),
+                duration,
+            })
+        })
+        .collect()
+}
+
+/// handling of trials missing some of their files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IncompleteTrials {
+    /// fail with [`TetError::InvalidLog`](TetError::InvalidLog) (default)
+    #[default]
+    Error,
+    /// ignore the trial
+    Skip,
+}
+
+/// load trials from a directory of files named after trials
+///
+/// A trial `name` consists of three files:
+///
+/// - `name.presented`: presented text
+/// - `name.transcribed`: transcribed text
+/// - `name.ms`: time required for entry transcribed text in milliseconds
+///
+/// A newline at the end of a text is not a part of the text.
+/// Trials are sorted by name, and other files are ignored.
+/// A trial missing some of its files is an error.
+pub fn from_dir<P: AsRef<Path>>(path: P) -> Result<Vec<Trial>> {
+    from_dir_with(path, IncompleteTrials::default())
+}
+
+/// load trials from a directory like [`from_dir`](from_dir), handling incomplete trials as specified
+pub fn from_dir_with<P: AsRef<Path>>(path: P, incomplete: IncompleteTrials) -> Result<Vec<Trial>> {
+    let invalid = |e: std::io::Error| TetError::InvalidLog(e.to_string());
+
+    // name -> (presented, transcribed, ms)
+    let mut files = BTreeMap::new();
+    for entry in std::fs::read_dir(path).map_err(invalid)? {
+        let path = entry.map_err(invalid)?.path();
+        let (name, extension) = match (path.file_stem(), path.extension()) {
+            (Some(name), Some(extension)) => (name.to_os_string(), extension.to_os_string()),
+            _ => continue,
+        };
+
+        let trial = files.entry(name).or_insert((None, None, None));
+        match extension.to_str() {
+            Some("presented") => trial.0 = Some(path),
+            Some("transcribed") => trial.1 = Some(path),
+            Some("ms") => trial.2 = Some(path),
+            _ => {}
+        }
+    }
+
+    let read = |path: &Path| std::fs::read_to_string(path).map_err(invalid);
+    let text = |path: &Path| {
+        read(path).map(|s| {
+            let s = s.strip_suffix('\n').unwrap_or(&s);
+            s.strip_suffix('\r').unwrap_or(s).to_string()
+        })
+    };
+
+    let mut trials = Vec::new();
+    for (name, paths) in files {
+        let (presented, transcribed, ms) = match paths {
+            (Some(presented), Some(transcribed), Some(ms)) => (presented, transcribed, ms),
+            (None, None, None) => continue,
+            _ => match incomplete {
+                IncompleteTrials::Error => {
+                    return Err(TetError::InvalidLog(format!("incomplete trial {:?}", name)));
+                }
+                IncompleteTrials::Skip => continue,
+            },
+        };
+
+        let ms = read(&ms)?;
+        let duration = ms.trim().parse::<u64>()
+            .map(Duration::from_millis)
+            .map_err(|_| TetError::InvalidLog(format!("invalid milliseconds {:?}", ms)))?;
+
+        trials.push(Trial {
+            presented: text(&presented)?,
+            transcribed: text(&transcribed)?,
+            duration,
+        });
+    }
+
+    Ok(trials)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(feature = "csv")]
+    use crate::TextEntryThroughput;
+
+    #[test]
+    fn from_dir_test() {
+        let dir = std::env::temp_dir().join(format!("tet_rs_from_dir_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let files = [
+            ("1.presented", "my watch fell in the water\n"),
+            ("1.transcribed", "my wacch fell in te water\n"),
+            ("1.ms", "7000\n"),
+            ("2.presented", "the quick brown fox"),
+            ("2.transcribed", "the quik brwn fix"),
+            ("2.ms", "4500"),
+            ("3.presented", "never too rich"),
+            ("README", "trials"),
+        ];
+        for (name, content) in files.iter() {
+            std::fs::write(dir.join(name), content).unwrap();
+        }
+
+        let error = from_dir(&dir);
+        let trials = from_dir_with(&dir, IncompleteTrials::Skip);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(error, Err(TetError::InvalidLog(_))));
+
+        let trials = trials.unwrap();
+        assert_eq!(trials, vec![
+            Trial {
+                presented: "my watch fell in the water".to_string(),
+                transcribed: "my wacch fell in te water".to_string(),
+                duration: Duration::from_secs(7),
+            },
+            Trial {
+                presented: "the quick brown fox".to_string(),
+                transcribed: "the quik brwn fix".to_string(),
+                duration: Duration::from_millis(4500),
+            },
+        ]);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn load_trials_csv_test() {
+        let log = "\
+presented,transcribed,seconds
+my watch fell in the water,my wacch fell in te water,7
+\"the quick brown fox\",the quik brwn fix,4.5
+";
+        let trials = load_trials_csv(log.as_bytes()).unwrap();
+        assert_eq!(trials.len(), 2);
+        assert_eq!(trials[1].presented, "the quick brown fox");
+        assert_eq!(trials[1].duration, Duration::from_millis(4500));
+
+        let tet = TextEntryThroughput::alphabet_letter_distribution();
+        let throughputs = tet.calc_trials(&trials);
+        for (trial, throughput) in trials.iter().zip(throughputs) {
+            assert_eq!(throughput, tet.calc(&trial.presented, &trial.transcribed, trial.duration));
+            assert!(throughput.unwrap() > 0.0);
+        }
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn invalid_log_test() {
+        let log = "presented,transcribed\nabc,abc\n";
+        assert!(matches!(load_trials_csv(log.as_bytes()), Err(TetError::InvalidLog(_))));
+
+        let log = "presented,transcribed,seconds\nabc,abc,-1\n";
+        assert!(matches!(load_trials_csv(log.as_bytes()), Err(TetError::InvalidLog(_))));
+    }
+}
@@ -0,0 +1,163 @@
+use crate::distribution::Source;
+use crate::optimal_alignments::{AlignmentOptions, NullEstimator, OptimalAlignments};
+use crate::error::{Result, TetError};
+#[cfg(feature = "serde1")]
+use serde::{Serialize, Deserialize};
+use std::collections::hash_map::Keys;
+use std::collections::HashMap;
+
+/// distribution of words
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq)]
+pub struct WordDistribution {
+    /// map of distribution
+    map: HashMap<String, f64>,
+}
+
+impl WordDistribution {
+    /// normalize frequencies of words
+    pub fn new(frequencies: HashMap<String, u128>) -> Self {
+        let n = frequencies.values()
+            .sum::<u128>() as f64;
+
+        let map = frequencies.into_iter()
+            .map(|(k, v)| {
+                (k, v as f64 / n)
+            })
+            .collect();
+
+        Self { map }
+    }
+
+    pub fn with_map(map: HashMap<String, f64>) -> Self {
+        Self { map }
+    }
+
+    /// H(X): entropy (bits/word)
+    pub fn hx(&self) -> f64 {
+        -self.map.values()
+            // 0 log 0 = 0
+            .filter(|&&pi| pi > 0.0)
+            .map(|&pi| {
+                pi * pi.log2()
+            })
+            .sum::<f64>()
+    }
+}
+
+impl Source for WordDistribution {
+    type Symbol = String;
+
+    fn p(&self, symbol: &String) -> Option<&f64> {
+        self.map.get(symbol)
+    }
+
+    fn symbols(&self) -> Keys<'_, String, f64> {
+        self.map.keys()
+    }
+
+    fn hx(&self) -> f64 {
+        WordDistribution::hx(self)
+    }
+}
+
+/// text entry throughput over words instead of characters
+///
+/// Texts are split into words on whitespace and words are aligned
+/// in the same way as characters.
+pub struct WordTextEntryThroughput {
+    distribution: WordDistribution,
+    options: AlignmentOptions,
+}
+
+impl WordTextEntryThroughput {
+    pub fn new(distribution: WordDistribution) -> Self {
+        Self {
+            distribution,
+            options: AlignmentOptions::default(),
+        }
+    }
+
+    /// set an estimator of p(NULL)
+    pub fn null_estimator(mut self, null_estimator: NullEstimator) -> Self {
+        self.options.null_estimator = null_estimator;
+        self
+    }
+
+    /// compute a text entry throughput (bits/s)
+    ///
+    /// - presented: presented text
+    /// - transcribed: transcribed text
+    /// - s: time in seconds required for entry transcribed text
+    pub fn calc(&self, presented: &str, transcribed: &str, s: std::time::Duration) -> Result<f64> {
+        if s.is_zero() {
+            return Err(TetError::ZeroDuration);
+        }
+        let (presented, transcribed) = (words(presented), words(transcribed));
+
+        let words_per_second = transcribed.len() as f64 / s.as_secs_f64();
+
+        let alignments = OptimalAlignments::from_symbols(
+            &presented, &transcribed, &self.distribution, &self.options,
+        )?;
+        // NaN or infinity is not a throughput
+        alignments.ixy()
+            .map(|ixy| ixy * words_per_second)
+            .filter(|throughput| throughput.is_finite())
+            .ok_or(TetError::UndefinedInformation)
+    }
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample_distribution() -> WordDistribution {
+        let frequencies = [
+            ("the", 4), ("quick", 2), ("brown", 2), ("fox", 2),
+            ("jumps", 2), ("over", 2), ("lazy", 1), ("dog", 1),
+        ].iter()
+            .map(|&(w, n)| (w.to_string(), n))
+            .collect();
+
+        WordDistribution::new(frequencies)
+    }
+
+    #[test]
+    fn hx_test() {
+        let distribution = sample_distribution();
+
+        // 1/4, 1/8 * 5, 1/16 * 2
+        assert!((distribution.hx() - 2.875).abs() < 0.00000000001);
+
+        // a word never seen contributes nothing
+        let mut frequencies = [("the", 1), ("fox", 1)].iter()
+            .map(|&(w, n)| (w.to_string(), n))
+            .collect::<HashMap<_, _>>();
+        frequencies.insert("dog".to_string(), 0);
+        assert_eq!(WordDistribution::new(frequencies).hx(), 1.0);
+    }
+
+    #[test]
+    fn word_text_entry_throughput_test() {
+        let tet = WordTextEntryThroughput::new(sample_distribution());
+        let s = std::time::Duration::from_secs(4);
+
+        let presented = "the quick brown fox jumps over the lazy dog";
+        let perfect = tet.calc(presented, presented, s).unwrap();
+        // H(X) * 9 words / 4 s
+        assert!((perfect - 2.875 * 9.0 / 4.0).abs() < 0.00000000001);
+
+        let substituted = tet.calc(presented, "the quick brown fox jumps over the lazy fox", s).unwrap();
+        assert!(substituted.is_finite());
+        assert!(0.0 < substituted && substituted < perfect);
+
+        assert_eq!(tet.calc(presented, presented, std::time::Duration::ZERO), Err(TetError::ZeroDuration));
+    }
+}